@@ -0,0 +1,98 @@
+//! Times `Gif::decode_with_options` of a single 1024x1024 frame twice: with the index buffer of `RasterData::parse`
+//! reserved up front for one index per pixel, and growing from empty as it did before.
+
+use gif_parser::gif::Gif;
+use gif_parser::options::DecodeOptions;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use std::{env, fs, process};
+
+const ROUNDS: u32 = 50;
+const SIZE: u16 = 1024;
+
+fn main() {
+    let data = single_frame_gif();
+    let path = env::temp_dir().join(format!("gif-parser-bench-decode-{}.gif", process::id()));
+    fs::write(&path, &data).unwrap();
+    let path = path.to_str().unwrap();
+    for (label, options) in [
+        ("preallocated", DecodeOptions::new()),
+        ("growing", DecodeOptions::new().grow_indices(true)),
+    ] {
+        // warm up the allocator and caches
+        let gif = Gif::decode_with_options(path, &options).unwrap();
+        assert_eq!(gif.first_frame_rgb().unwrap().2[3..6], [0xFF, 0xFF, 0xFF]);
+        let per_decode = time(|| {
+            black_box(Gif::decode_with_options(black_box(path), &options).unwrap());
+        });
+        println!(
+            "{SIZE}x{SIZE} frame, {} bytes, {label} indices: {per_decode:.2?} per decode",
+            data.len()
+        );
+    }
+    fs::remove_file(path).unwrap();
+}
+
+/// Average time of `ROUNDS` runs of `run`.
+fn time(mut run: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        run();
+    }
+    start.elapsed() / ROUNDS
+}
+
+/// A GIF89a of one `SIZE` x `SIZE` frame cycling through a four color global table.
+/// The image data sends a Clear Code before every pair of pixels, so the codes stay 3 bits wide
+/// and need no dictionary to write.
+fn single_frame_gif() -> Vec<u8> {
+    const CLEAR: u32 = 4;
+    const END: u32 = 5;
+
+    let mut out = b"GIF89a".to_vec();
+    out.extend(SIZE.to_le_bytes());
+    out.extend(SIZE.to_le_bytes());
+    out.extend([0xF1, 0x00, 0x00]);
+    out.extend([
+        0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0xFF, 0x00,
+    ]);
+    out.push(0x2C);
+    out.extend([0, 0, 0, 0]);
+    out.extend(SIZE.to_le_bytes());
+    out.extend(SIZE.to_le_bytes());
+    out.push(0x00);
+
+    let pixels = SIZE as u32 * SIZE as u32;
+    let mut codes = Vec::new();
+    for pixel in 0..pixels {
+        if pixel % 2 == 0 {
+            codes.push(CLEAR);
+        }
+        codes.push(pixel % 4);
+    }
+    codes.push(END);
+
+    let mut data = Vec::new();
+    let (mut bits, mut bit_count) = (0u32, 0);
+    for code in codes {
+        bits |= code << bit_count;
+        bit_count += 3;
+        while bit_count >= 8 {
+            data.push(bits as u8);
+            bits >>= 8;
+            bit_count -= 8;
+        }
+    }
+    if bit_count > 0 {
+        data.push(bits as u8);
+    }
+
+    out.push(2);
+    for block in data.chunks(255) {
+        out.push(block.len() as u8);
+        out.extend(block);
+    }
+    out.push(0x00);
+    out.push(0x3B);
+    out
+}
@@ -0,0 +1,32 @@
+//! Times `DescriptorGroup::render_rgb` over the fixtures.
+//!
+//! Run it once with and once without the `simd` feature and compare:
+//! `cargo bench --bench render_rgb` and `cargo bench --bench render_rgb --features simd`.
+
+use gif_parser::gif::Gif;
+use std::hint::black_box;
+use std::time::Instant;
+
+const ROUNDS: u32 = 200;
+
+fn main() {
+    for name in ["a.gif", "clown.gif", "stars.gif"] {
+        let gif = Gif::decode(&format!("{}/res/{name}", env!("CARGO_MANIFEST_DIR"))).unwrap();
+        let pixels: usize = gif
+            .descriptor_groups
+            .iter()
+            .map(|group| group.raster_data.indices().len())
+            .sum();
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            for group in &gif.descriptor_groups {
+                black_box(group.render_rgb(gif.global_color_map.as_ref()));
+            }
+        }
+        let elapsed = start.elapsed();
+        let per_round = elapsed / ROUNDS;
+        let megapixels_per_second = pixels as f64 * ROUNDS as f64 / elapsed.as_secs_f64() / 1e6;
+        println!("{name:>10}: {pixels:>8} pixels, {per_round:>10.2?} per round, {megapixels_per_second:>8.1} Mpx/s");
+    }
+}
@@ -0,0 +1,16 @@
+#![no_main]
+
+use gif_parser::gif::Gif;
+use libfuzzer_sys::fuzz_target;
+
+// Any input must come back as an error instead of a panic.
+// Streams that do decode are encoded again, which must not panic either.
+//
+// Seed a run with the fixtures instead of copies of them, from the repository root:
+// `mkdir -p fuzz/corpus/decode_bytes && cargo +nightly fuzz run decode_bytes fuzz/corpus/decode_bytes res`
+// New inputs go to the first directory, which stays out of git; `res/` is only read.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(gif) = Gif::decode_bytes(data) {
+        let _ = gif.encode();
+    }
+});
@@ -0,0 +1,117 @@
+use crate::gif::Gif;
+use crate::options::DecodeOptions;
+use std::time::Duration;
+
+/// Playback information of a GIF: how long each frame is shown and how often the animation repeats.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Animation {
+    /// Display time of every frame, taken from the Delay Time of its Graphic Control Extension.
+    /// Frames without a Graphic Control Extension have no delay.
+    frame_delays: Vec<Duration>,
+
+    /// Loop count of the NETSCAPE2.0 Application Extension.
+    /// `Some(0)` loops forever, `None` means the extension is absent and the animation plays once.
+    loop_count: Option<u16>,
+}
+
+impl Animation {
+    pub fn new(gif: &Gif) -> Self {
+        Self::with_options(gif, &DecodeOptions::default())
+    }
+
+    /// Like `new`, but raises every delay below `DecodeOptions::min_delay` to that minimum.
+    pub fn with_options(gif: &Gif, options: &DecodeOptions) -> Self {
+        let min_delay = options.get_min_delay().unwrap_or(0);
+        let frame_delays = gif
+            .descriptor_groups
+            .iter()
+            .map(|group| {
+                let delay_time = group
+                    .graphic_control_extension
+                    .as_ref()
+                    .map_or(0, |gce| gce.delay_time())
+                    .max(min_delay);
+                Duration::from_millis(delay_time as u64 * 10)
+            })
+            .collect();
+
+        Animation {
+            frame_delays,
+            loop_count: gif.loop_count(),
+        }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_delays.len()
+    }
+
+    pub fn is_animated(&self) -> bool {
+        self.frame_count() > 1
+    }
+
+    pub fn loop_count(&self) -> Option<u16> {
+        self.loop_count
+    }
+
+    /// `true` only for a NETSCAPE2.0 loop count of 0, which repeats forever.
+    /// Any other count stops after that many repeats, and without the extension the animation plays once.
+    pub fn is_infinite_loop(&self) -> bool {
+        self.loop_count == Some(0)
+    }
+
+    pub fn frame_delays(&self) -> &[Duration] {
+        &self.frame_delays
+    }
+
+    /// Duration of a single pass over all frames.
+    pub fn total_duration(&self) -> Duration {
+        self.frame_delays.iter().sum()
+    }
+
+    /// `(frame index, start time)` of every frame within a single pass, the schedule a player follows.
+    /// Start times add up the same delays as `total_duration`, so they include any `min_delay` clamping.
+    pub fn to_frame_timeline(&self) -> Vec<(usize, Duration)> {
+        self.frame_delays
+            .iter()
+            .scan(Duration::ZERO, |start, &delay| {
+                let frame_start = *start;
+                *start += delay;
+                Some(frame_start)
+            })
+            .enumerate()
+            .collect()
+    }
+
+    /// Index of the frame on screen `elapsed` after playback started, `None` without frames.
+    /// Looping animations wrap around the total duration; once an animation has played
+    /// `loop_count + 1` times, or once without a loop count, its last frame stays on screen.
+    pub fn frame_at(&self, elapsed: Duration) -> Option<usize> {
+        let last_frame = self.frame_count().checked_sub(1)?;
+        let total_duration = self.total_duration();
+        if total_duration.is_zero() {
+            return Some(last_frame);
+        }
+        let plays = match self.loop_count {
+            Some(0) => None,
+            Some(loop_count) => Some(loop_count as u32 + 1),
+            None => Some(1),
+        };
+        if plays
+            .and_then(|plays| total_duration.checked_mul(plays))
+            .is_some_and(|playback| elapsed >= playback)
+        {
+            return Some(last_frame);
+        }
+
+        let mut position =
+            Duration::from_nanos((elapsed.as_nanos() % total_duration.as_nanos()) as u64);
+        for (index, &delay) in self.frame_delays.iter().enumerate() {
+            if position < delay {
+                return Some(index);
+            }
+            position -= delay;
+        }
+        Some(last_frame)
+    }
+}
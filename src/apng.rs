@@ -0,0 +1,45 @@
+use crate::error::GifError;
+use crate::gif::Gif;
+use crate::render::Compositor;
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+impl Gif {
+    /// Writes the composited frames as an animated PNG, keeping each frame's delay and the loop count.
+    /// A NETSCAPE2.0 loop count of `n` repeats the animation `n` times after the first play, 0 loops forever.
+    pub fn save_apng(&self, file_path: &str) -> Result<(), GifError> {
+        let screen_descriptor = &self.logical_screen_descriptor;
+        let compositor = Compositor::new(self)?;
+        let file = File::create(file_path)?;
+        let mut encoder = png::Encoder::new(
+            BufWriter::new(file),
+            screen_descriptor.logical_screen_width() as u32,
+            screen_descriptor.logical_screen_height() as u32,
+        );
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let num_plays = match self.loop_count() {
+            None => 1,
+            Some(0) => 0,
+            Some(loop_count) => loop_count as u32 + 1,
+        };
+        encoder
+            .set_animated(self.descriptor_groups.len() as u32, num_plays)
+            .map_err(io::Error::from)?;
+
+        let mut writer = encoder.write_header().map_err(io::Error::from)?;
+        for (group, pixels) in self.descriptor_groups.iter().zip(compositor) {
+            let delay_time = group
+                .graphic_control_extension
+                .as_ref()
+                .map_or(0, |gce| gce.delay_time());
+            writer
+                .set_frame_delay(delay_time, 100)
+                .map_err(io::Error::from)?;
+            writer.write_image_data(&pixels).map_err(io::Error::from)?;
+        }
+        writer.finish().map_err(io::Error::from)?;
+        Ok(())
+    }
+}
@@ -1,24 +1,186 @@
+use gif_parser::animation::Animation;
 use gif_parser::gif;
+use gif_parser::options::DecodeOptions;
 use std::env;
+#[cfg(feature = "apng")]
+use std::fs;
+#[cfg(feature = "apng")]
+use std::io;
+#[cfg(feature = "apng")]
+use std::path::Path;
 use std::result;
 
 type Result<T> = result::Result<T, ()>;
 
 fn main() -> Result<()> {
     let mut args = env::args();
-    println!("Running {program}", program = args.next().unwrap());
-    match args.next() {
-        Some(file_path) => {
-            let gif_sign: gif::GifSignature = gif::Gif::decode(&file_path).signature;
-            println!("INFO: loaded gif version {gif_sign:?} from file {file_path}");
-        }
+    let program = args.next().unwrap();
 
-        None => {
-            let file_path = "res/stars.gif";
-            println!("WARN: No file path provided using default path: {file_path}");
-            let gif_sign: gif::GifSignature = gif::Gif::decode(file_path).signature;
-            println!("INFO: loaded gif version {gif_sign:?} from file {file_path}");
+    let mut json = false;
+    let mut info = false;
+    let mut lenient = false;
+    let mut min_delay: Option<u16> = None;
+    let mut extract_frames: Option<String> = None;
+    let mut file_path: Option<String> = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--info" => info = true,
+            "--lenient" => lenient = true,
+            "--min-delay" => {
+                let value = args.next().ok_or_else(|| {
+                    eprintln!("ERROR: --min-delay needs a delay in hundredths of a second");
+                })?;
+                min_delay = Some(value.parse().map_err(|err| {
+                    eprintln!("ERROR: Invalid --min-delay {value}: {err}");
+                })?)
+            }
+            "--extract-frames" => {
+                extract_frames = Some(args.next().ok_or_else(|| {
+                    eprintln!("ERROR: --extract-frames needs an output directory");
+                })?)
+            }
+            _ => file_path = Some(arg),
         }
-    };
+    }
+
+    let mut options = DecodeOptions::new().lenient_truncation(lenient);
+    if let Some(min_delay) = min_delay {
+        options = options.min_delay(min_delay);
+    }
+    if json {
+        let file_path = file_path.unwrap_or_else(|| String::from("res/stars.gif"));
+        return print_json(&decode(&file_path, &options)?);
+    }
+
+    println!("Running {program}");
+    let file_path = file_path.unwrap_or_else(|| {
+        let file_path = String::from("res/stars.gif");
+        println!("WARN: No file path provided using default path: {file_path}");
+        file_path
+    });
+    println!("INFO: Loading file {file_path}...");
+    let gif = decode(&file_path, &options)?;
+    for warning in &gif.warnings {
+        println!("WARN: {warning}");
+    }
+    if info {
+        print_info(&gif, &options);
+        return Ok(());
+    }
+    if let Some(dir) = extract_frames {
+        return extract_png_frames(&gif, &dir);
+    }
+    println!(
+        "INFO: Magic value: {signature:#?}",
+        signature = gif.signature
+    );
+    println!(
+        "INFO: Screen Descriptor: {logical_screen_descriptor:#?}",
+        logical_screen_descriptor = gif.logical_screen_descriptor
+    );
+    if let Some(gcm) = &gif.global_color_map {
+        println!("INFO: {gcm}");
+    }
+    println!(
+        "INFO: Image Descriptors: {count} read",
+        count = gif.descriptor_groups.len()
+    );
+    println!(
+        "INFO: loaded gif version {gif_sign:?} from file {file_path}",
+        gif_sign = gif.signature
+    );
+    Ok(())
+}
+
+fn decode(file_path: &str, options: &DecodeOptions) -> Result<gif::Gif> {
+    gif::Gif::decode_with_options(file_path, options).map_err(|err| {
+        eprintln!("ERROR: Unable to decode gif {file_path}: {err}");
+    })
+}
+
+fn print_info(gif: &gif::Gif, options: &DecodeOptions) {
+    let animation = Animation::with_options(gif, options);
+    println!(
+        "INFO: Frame count: {count}",
+        count = animation.frame_count()
+    );
+    println!(
+        "INFO: Animated: {animated}",
+        animated = animation.is_animated()
+    );
+    match animation.loop_count() {
+        Some(0) => println!("INFO: Loop count: infinite"),
+        Some(loop_count) => println!("INFO: Loop count: {loop_count}"),
+        None => println!("INFO: Loop count: none (plays once)"),
+    }
+    for (index, delay) in animation.frame_delays().iter().enumerate() {
+        println!("INFO: Frame {index} delay: {delay:?}");
+    }
+    println!(
+        "INFO: Total duration: {duration:?}",
+        duration = animation.total_duration()
+    );
+}
+
+/// Writes every composited frame to `dir` as `frame_000.png`, `frame_001.png`, ...
+#[cfg(feature = "apng")]
+fn extract_png_frames(gif: &gif::Gif, dir: &str) -> Result<()> {
+    let frames = gif.rgba_frames().map_err(|err| {
+        eprintln!("ERROR: Unable to render frames: {err}");
+    })?;
+    fs::create_dir_all(dir).map_err(|err| {
+        eprintln!("ERROR: Unable to create directory {dir}: {err}");
+    })?;
+    let width = gif.logical_screen_descriptor.logical_screen_width() as u32;
+    let height = gif.logical_screen_descriptor.logical_screen_height() as u32;
+    let digits = frames.len().saturating_sub(1).to_string().len().max(3);
+    for (index, pixels) in frames.iter().enumerate() {
+        let path = Path::new(dir).join(format!("frame_{index:0digits$}.png"));
+        write_png(&path, width, height, pixels).map_err(|err| {
+            eprintln!(
+                "ERROR: Unable to write {path}: {err}",
+                path = path.display()
+            );
+        })?;
+    }
+    println!(
+        "INFO: Extracted {count} frames to {dir}",
+        count = frames.len()
+    );
     Ok(())
 }
+
+#[cfg(feature = "apng")]
+fn write_png(path: &Path, width: u32, height: u32, pixels: &[u8]) -> io::Result<()> {
+    let file = fs::File::create(path)?;
+    let mut encoder = png::Encoder::new(io::BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::from)?;
+    writer.write_image_data(pixels).map_err(io::Error::from)?;
+    writer.finish().map_err(io::Error::from)
+}
+
+#[cfg(not(feature = "apng"))]
+fn extract_png_frames(_gif: &gif::Gif, _dir: &str) -> Result<()> {
+    eprintln!(
+        "ERROR: --extract-frames requires the decoder to be built with the `image-integration` or `apng` feature"
+    );
+    Err(())
+}
+
+#[cfg(feature = "serde")]
+fn print_json(gif: &gif::Gif) -> Result<()> {
+    let json = serde_json::to_string_pretty(gif).map_err(|err| {
+        eprintln!("ERROR: Unable to serialize gif to json: {err}");
+    })?;
+    println!("{json}");
+    Ok(())
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_json(_gif: &gif::Gif) -> Result<()> {
+    eprintln!("ERROR: --json requires the decoder to be built with the `serde` feature");
+    Err(())
+}
@@ -0,0 +1,101 @@
+use crate::error::GifError;
+use crate::extension::{ApplicationExtension, CommentExtension, PlainTextExtension};
+use crate::gif::{
+    DescriptorGroup, GifSignature, GlobalColorMap, LogicalScreenDescriptor, Terminator,
+};
+use std::io::Write;
+
+/// Writes a parsed block back out as the bytes of a GIF Data Stream, the inverse of `GifBlock::parse`.
+pub(crate) trait EncodeBlock {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// Splits `data` into data sub-blocks of at most 255 bytes, followed by the Block Terminator.
+pub(crate) fn write_data_sub_blocks(out: &mut Vec<u8>, data: &[u8]) {
+    for block in data.chunks(u8::MAX as usize) {
+        out.push(block.len() as u8);
+        out.extend_from_slice(block);
+    }
+    out.push(0);
+}
+
+/// Builds a GIF Data Stream block by block, writing each block to `W` as soon as it is complete:
+/// a `File`, a socket or just a `Vec<u8>`.
+/// Write the header first, then any extensions and frames, and `finish` with the Trailer.
+///
+/// Image data is compressed again from the decoded indices, so the output is equivalent but not
+/// necessarily byte-identical to the stream the blocks were parsed from.
+#[derive(Debug)]
+pub struct GifEncoder<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> GifEncoder<W> {
+    pub fn new(writer: W) -> Self {
+        GifEncoder { writer }
+    }
+
+    /// Writes the Header, the Logical Screen Descriptor and the Global Color Table.
+    pub fn write_header(
+        &mut self,
+        signature: &GifSignature,
+        logical_screen_descriptor: &LogicalScreenDescriptor,
+        global_color_map: Option<&GlobalColorMap>,
+    ) -> Result<(), GifError> {
+        let mut out = Vec::new();
+        signature.encode(&mut out);
+        logical_screen_descriptor.encode(&mut out);
+        if let Some(global_color_map) = global_color_map {
+            global_color_map.encode(&mut out);
+        }
+        self.write_block(&out)
+    }
+
+    pub fn write_application_extension(
+        &mut self,
+        application_extension: &ApplicationExtension,
+    ) -> Result<(), GifError> {
+        let mut out = Vec::new();
+        application_extension.encode(&mut out);
+        self.write_block(&out)
+    }
+
+    pub fn write_comment_extension(
+        &mut self,
+        comment_extension: &CommentExtension,
+    ) -> Result<(), GifError> {
+        let mut out = Vec::new();
+        comment_extension.encode(&mut out);
+        self.write_block(&out)
+    }
+
+    /// Writes the text's Graphic Control Extension and the Plain Text Extension.
+    pub fn write_plain_text_extension(
+        &mut self,
+        plain_text_extension: &PlainTextExtension,
+    ) -> Result<(), GifError> {
+        let mut out = Vec::new();
+        plain_text_extension.encode(&mut out);
+        self.write_block(&out)
+    }
+
+    /// Writes the frame's Graphic Control Extension, Image Descriptor, Local Color Table and image data.
+    pub fn write_frame(&mut self, descriptor_group: &DescriptorGroup) -> Result<(), GifError> {
+        let mut out = Vec::new();
+        descriptor_group.encode(&mut out);
+        self.write_block(&out)
+    }
+
+    /// Writes the Trailer, flushes and hands back the writer.
+    pub fn finish(mut self) -> Result<W, GifError> {
+        let mut out = Vec::new();
+        Terminator {}.encode(&mut out);
+        self.write_block(&out)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_block(&mut self, block: &[u8]) -> Result<(), GifError> {
+        Ok(self.writer.write_all(block)?)
+    }
+}
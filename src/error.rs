@@ -0,0 +1,269 @@
+use crate::gif::GifVersion;
+use std::{error, fmt, io, string};
+
+#[derive(Debug)]
+pub enum GifError {
+    /// Opening or reading the underlying file failed.
+    Io(io::Error),
+
+    /// The LZW Minimum Code Size byte at `offset`, in front of the image data, must be in `2..=8`;
+    /// anything else means the stream is corrupt.
+    InvalidLzwCodeSize { size: u8, offset: usize },
+
+    /// The LZW code stream referenced a code that is not (yet) in the code table.
+    /// `offset` is the position of the image data byte the code starts in.
+    InvalidLzwCode { code: u16, offset: usize },
+
+    /// The buffer ended before the Trailer (`0x3B`) was reached.
+    /// `offset` is the position of the first byte that was missing.
+    UnexpectedEof { offset: usize },
+
+    /// A byte other than an Extension Introducer (`0x21`), Image Separator (`0x2C`)
+    /// or Trailer (`0x3B`) was found where the next block should start.
+    UnknownBlock { label: u8, offset: usize },
+
+    /// The requested frame index is past the last image in the stream.
+    FrameNotFound { index: usize, frame_count: usize },
+
+    /// The data stream is not of the version required by `DecodeOptions::version`.
+    UnsupportedVersion(GifVersion),
+
+    /// The Image Descriptor starting at `offset` declared a width or height of 0, which leaves no raster to decode.
+    ZeroSizedFrame { offset: usize },
+
+    /// Frame `frame` extends past the Logical Screen; each image must fit within its boundaries.
+    FrameOutOfBounds { frame: usize },
+
+    /// Reserved bits of the Image Descriptor or Graphic Control Extension of frame `frame` are not 0.
+    ReservedBitsSet { frame: usize },
+
+    /// The Background Color Index does not point into the Global Color Table.
+    BackgroundColorOutOfRange(u8),
+
+    /// Frame `frame` has neither a Local nor a Global Color Table to resolve its pixels.
+    MissingColorTable { frame: usize },
+
+    /// A pixel of frame `frame` uses color `index`, which is past the end of its active color table.
+    ColorIndexOutOfRange { frame: usize, index: u8 },
+
+    /// The Block Size byte at `offset` declared a size different from the number of bytes the extension's fields occupy.
+    BlockSizeMismatch {
+        declared: usize,
+        consumed: usize,
+        offset: usize,
+    },
+
+    /// The header text is not valid UTF-8.
+    Utf8(string::FromUtf8Error),
+
+    /// The Signature field at `offset` is not "GIF".
+    InvalidSignature { found: String, offset: usize },
+
+    /// The Version field at `offset` is neither "87a" nor "89a".
+    /// `offset` is 0 when the version comes from `GifVersion::from_str` rather than a data stream.
+    UnknownVersion { version: String, offset: usize },
+
+    /// A block did not start with its fixed introducer, e.g. the Image Separator (`0x2C`) or Trailer (`0x3B`).
+    UnexpectedByte {
+        expected: u8,
+        found: u8,
+        offset: usize,
+    },
+
+    /// The stream ended without a Trailer (`0x3B`), e.g. a truncated file decoded with `DecodeOptions::lenient_truncation`.
+    MissingTrailer,
+
+    /// The frame whose image data reached `offset` decompressed to more than `limit` bytes, see `DecodeOptions::max_decoded_bytes`.
+    DecodeLimitExceeded { limit: usize, offset: usize },
+
+    /// The color table starting at `offset` declares `declared` bytes but only `available` bytes are left in the data stream.
+    TruncatedColorTable {
+        declared: usize,
+        available: usize,
+        offset: usize,
+    },
+
+    /// The color table starting at `offset` would hold `len` colors, more than the 256 an index byte can address.
+    InvalidColorTableSize { len: usize, offset: usize },
+
+    /// A `width` x `height` pixel buffer is too large to allocate. `offset` is where the Logical Screen Descriptor
+    /// bounding its size starts, `None` for a `Canvas` created on its own.
+    ImageTooLarge {
+        width: u16,
+        height: u16,
+        offset: Option<usize>,
+    },
+
+    /// The stream goes past a resource limit of `DecodeOptions`: `limit` names the setting, e.g. `max_frames`,
+    /// and `value` is what the block starting at `offset` asked for.
+    LimitExceeded {
+        limit: &'static str,
+        value: usize,
+        max: usize,
+        offset: usize,
+    },
+
+    /// The rectangle passed to `Gif::crop` is empty or reaches past the Logical Screen.
+    CropOutOfBounds {
+        left: u16,
+        top: u16,
+        width: u16,
+        height: u16,
+    },
+
+    /// The flag passed to `DecodeOptions::cancel_flag` was set while decoding.
+    Cancelled,
+}
+
+impl fmt::Display for GifError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GifError::Io(err) => write!(f, "io error: {err}"),
+            GifError::InvalidLzwCodeSize { size, offset } => {
+                write!(
+                    f,
+                    "invalid LZW minimum code size {size} at offset {offset}, expected 2..=8"
+                )
+            }
+            GifError::InvalidLzwCode { code, offset } => {
+                write!(f, "invalid LZW code {code} at offset {offset}")
+            }
+            GifError::UnexpectedEof { offset } => {
+                write!(f, "unexpected end of data at offset {offset}")
+            }
+            GifError::UnknownBlock { label, offset } => {
+                write!(f, "unknown block {label:#04x} at offset {offset}")
+            }
+            GifError::FrameNotFound { index, frame_count } => {
+                write!(f, "frame {index} not found, gif has {frame_count} frames")
+            }
+            GifError::UnsupportedVersion(version) => {
+                write!(f, "gif version {version:?} is not accepted")
+            }
+            GifError::ZeroSizedFrame { offset } => {
+                write!(
+                    f,
+                    "image descriptor at offset {offset} has a zero width or height"
+                )
+            }
+            GifError::FrameOutOfBounds { frame } => {
+                write!(f, "frame {frame} does not fit within the logical screen")
+            }
+            GifError::ReservedBitsSet { frame } => {
+                write!(f, "frame {frame} has reserved bits set")
+            }
+            GifError::BackgroundColorOutOfRange(index) => {
+                write!(
+                    f,
+                    "background color index {index} is outside the global color table"
+                )
+            }
+            GifError::MissingColorTable { frame } => {
+                write!(f, "frame {frame} has no color table")
+            }
+            GifError::ColorIndexOutOfRange { frame, index } => {
+                write!(
+                    f,
+                    "frame {frame} uses color index {index} outside its color table"
+                )
+            }
+            GifError::BlockSizeMismatch {
+                declared,
+                consumed,
+                offset,
+            } => write!(
+                f,
+                "block size {declared} declared at offset {offset} but {consumed} bytes consumed"
+            ),
+            GifError::Utf8(err) => write!(f, "invalid utf-8 in header: {err}"),
+            GifError::InvalidSignature { found, offset } => {
+                write!(
+                    f,
+                    "expected signature \"GIF\" but found {found:?} at offset {offset}"
+                )
+            }
+            GifError::UnknownVersion { version, offset } => {
+                write!(f, "gif version {version:?} at offset {offset} not recognized")
+            }
+            GifError::UnexpectedByte {
+                expected,
+                found,
+                offset,
+            } => write!(
+                f,
+                "expected {expected:#04x} but found {found:#04x} at offset {offset}"
+            ),
+            GifError::MissingTrailer => write!(f, "gif data stream has no trailer"),
+            GifError::DecodeLimitExceeded { limit, offset } => write!(
+                f,
+                "decoded frame exceeds the limit of {limit} bytes at offset {offset}"
+            ),
+            GifError::InvalidColorTableSize { len, offset } => write!(
+                f,
+                "color table of {len} colors at offset {offset} is larger than 256 colors"
+            ),
+            GifError::TruncatedColorTable {
+                declared,
+                available,
+                offset,
+            } => write!(
+                f,
+                "color table of {declared} bytes declared at offset {offset} but only {available} bytes left"
+            ),
+            GifError::ImageTooLarge {
+                width,
+                height,
+                offset: Some(offset),
+            } => write!(
+                f,
+                "a {width}x{height} image of the screen declared at offset {offset} is too large to allocate"
+            ),
+            GifError::ImageTooLarge {
+                width,
+                height,
+                offset: None,
+            } => write!(f, "a {width}x{height} image is too large to allocate"),
+            GifError::LimitExceeded {
+                limit,
+                value,
+                max,
+                offset,
+            } => write!(
+                f,
+                "{limit} exceeded at offset {offset}: {value} is more than {max}"
+            ),
+            GifError::CropOutOfBounds {
+                left,
+                top,
+                width,
+                height,
+            } => write!(
+                f,
+                "crop of {width}x{height} at ({left}, {top}) is empty or outside the logical screen"
+            ),
+            GifError::Cancelled => write!(f, "decoding was cancelled"),
+        }
+    }
+}
+
+impl error::Error for GifError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            GifError::Io(err) => Some(err),
+            GifError::Utf8(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GifError {
+    fn from(value: io::Error) -> Self {
+        GifError::Io(value)
+    }
+}
+
+impl From<string::FromUtf8Error> for GifError {
+    fn from(value: string::FromUtf8Error) -> Self {
+        GifError::Utf8(value)
+    }
+}
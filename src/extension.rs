@@ -0,0 +1,635 @@
+use crate::encoder::{self, EncodeBlock};
+use crate::error::GifError;
+use crate::gif::EXTENSION_INTRODUCER;
+use crate::parser::{ByteSource, GifBlock};
+use std::borrow::Cow;
+use std::time::Duration;
+
+/// Identifies the current block as a Graphic Control Extension.
+pub(crate) const GRAPHIC_CONTROL_LABEL: u8 = 0xF9;
+/// Identifies the current block as an Application Extension.
+pub(crate) const APPLICATION_EXTENSION_LABEL: u8 = 0xFF;
+/// Identifies the current block as a Plain Text Extension.
+pub(crate) const PLAIN_TEXT_LABEL: u8 = 0x01;
+/// Identifies the current block as a Comment Extension.
+pub(crate) const COMMENT_LABEL: u8 = 0xFE;
+
+/// Fails when the fixed fields read since `mark` don't add up to the declared Block Size.
+fn check_block_size<S: ByteSource + ?Sized>(
+    buf: &S,
+    mark: usize,
+    block_size: u8,
+) -> Result<(), GifError> {
+    let consumed = buf.bytes_since(mark);
+    if consumed != block_size as usize {
+        return Err(GifError::BlockSizeMismatch {
+            declared: block_size as usize,
+            consumed,
+            // the Block Size byte is right in front of the mark
+            offset: mark - 1,
+        });
+    }
+    Ok(())
+}
+
+/// Reads the data sub-blocks of an extension, keeping their size bytes when `raw`,
+/// and returns them with the number of sub-blocks and the total size of their payloads.
+fn read_counted_sub_blocks<S: ByteSource + ?Sized>(
+    buf: &mut S,
+    raw: bool,
+) -> Result<(Vec<u8>, usize, usize), GifError> {
+    let mark = buf.mark();
+    let data = if raw {
+        buf.read_data_sub_blocks_raw()?
+    } else {
+        buf.read_data_sub_blocks()?
+    };
+    // every sub-block is a size byte and its payload, and the Block Terminator ends the chain
+    let sub_blocks_size = buf.bytes_since(mark) - 1;
+    let sub_block_count = if raw {
+        let (mut position, mut count) = (0, 0);
+        while let Some(&block_size) = data.get(position) {
+            position += 1 + block_size as usize;
+            count += 1;
+        }
+        count
+    } else {
+        sub_blocks_size - data.len()
+    };
+    Ok((data, sub_block_count, sub_blocks_size - sub_block_count))
+}
+
+/// How the graphic is to be treated after being displayed, the 3 bit Disposal Method of the Graphic Control Extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum DisposalMethod {
+    /// 0 - No disposal specified. The decoder is not required to take any action.
+    None,
+    /// 1 - Do not dispose. The graphic is to be left in place.
+    DoNotDispose,
+    /// 2 - Restore to background color. The area used by the graphic must be restored to the background color.
+    RestoreBackground,
+    /// 3 - Restore to previous. The area overwritten by the graphic is restored to what was there prior to rendering the graphic.
+    RestorePrevious,
+    /// 4-7 - To be defined.
+    Reserved(u8),
+}
+
+impl From<u8> for DisposalMethod {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => DisposalMethod::None,
+            1 => DisposalMethod::DoNotDispose,
+            2 => DisposalMethod::RestoreBackground,
+            3 => DisposalMethod::RestorePrevious,
+            _ => DisposalMethod::Reserved(value),
+        }
+    }
+}
+
+impl From<DisposalMethod> for u8 {
+    fn from(value: DisposalMethod) -> Self {
+        match value {
+            DisposalMethod::None => 0,
+            DisposalMethod::DoNotDispose => 1,
+            DisposalMethod::RestoreBackground => 2,
+            DisposalMethod::RestorePrevious => 3,
+            DisposalMethod::Reserved(value) => value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GCEPackedFields {
+    /// Reserved for future use, 3 bits.
+    reserved: u8,
+
+    /// Disposal Method - Indicates the way in which the graphic is to be treated after being displayed.
+    disposal_method: DisposalMethod,
+
+    /// User Input Flag - Indicates whether or not user input is expected before continuing.
+    /// If the flag is set, processing will continue when user input is entered.
+    /// When a Delay Time is used and the User Input Flag is set, processing will continue when user input is received or when the delay time expires, whichever occurs first.
+    user_input_flag: bool,
+
+    /// Transparency Flag - Indicates whether a transparency index is given in the Transparent Index field.
+    /// (This field is the least significant bit of the byte.)
+    transparent_color_flag: bool,
+}
+
+impl GifBlock for GCEPackedFields {
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        let m_u8: u8 = buf.read_u8()?;
+        let reserved = (m_u8 >> 5) & 0b111;
+        let disposal_method = DisposalMethod::from((m_u8 >> 2) & 0b111);
+        let user_input_flag = (m_u8 >> 1) & 0b1 == 1;
+        let transparent_color_flag = m_u8 & 0b1 == 1;
+
+        Ok(GCEPackedFields {
+            reserved,
+            disposal_method,
+            user_input_flag,
+            transparent_color_flag,
+        })
+    }
+}
+
+impl EncodeBlock for GCEPackedFields {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(
+            (self.reserved & 0b111) << 5
+                | (u8::from(self.disposal_method) & 0b111) << 2
+                | (self.user_input_flag as u8) << 1
+                | self.transparent_color_flag as u8,
+        );
+    }
+}
+
+/// The Graphic Control Extension contains parameters used when processing a graphic rendering block.
+/// The scope of this extension is the first graphic rendering block to follow.
+/// The extension contains only one data sub-block.
+/// This block is OPTIONAL; at most one Graphic Control Extension may precede a graphic rendering block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GraphicControlExtension {
+    packed_fields: GCEPackedFields,
+
+    /// Delay Time - If not 0, this field specifies the number of hundredths (1/100) of a second to wait before continuing with the processing of the Data Stream.
+    /// The clock starts ticking immediately after the graphic is rendered.
+    delay_time: u16,
+
+    /// Transparency Index - The Transparency Index is such that when encountered, the corresponding pixel of the display device is not modified and processing goes on to the next pixel.
+    /// The index is present if and only if the Transparency Flag is set to 1.
+    transparent_color_index: u8,
+}
+
+impl GifBlock for GraphicControlExtension {
+    /// Parses the extension body; the Extension Introducer and Graphic Control Label are already consumed.
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        let block_size: u8 = buf.read_u8()?;
+        let mark = buf.mark();
+        let packed_fields = GCEPackedFields::parse(buf)?;
+        let delay_time = buf.read_le_u16()?;
+        let transparent_color_index = buf.read_u8()?;
+        check_block_size(buf, mark, block_size)?;
+        buf.skip_data_sub_blocks()?;
+
+        Ok(GraphicControlExtension {
+            packed_fields,
+            delay_time,
+            transparent_color_index,
+        })
+    }
+}
+
+impl EncodeBlock for GraphicControlExtension {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&[EXTENSION_INTRODUCER, GRAPHIC_CONTROL_LABEL, 4]);
+        self.packed_fields.encode(out);
+        out.extend_from_slice(&self.delay_time.to_le_bytes());
+        out.push(self.transparent_color_index);
+        encoder::write_data_sub_blocks(out, &[]);
+    }
+}
+
+impl GraphicControlExtension {
+    pub fn delay_time(&self) -> u16 {
+        self.delay_time
+    }
+
+    /// The Delay Time, which counts hundredths of a second, as a `Duration`.
+    pub fn delay_duration(&self) -> Duration {
+        Duration::from_millis(self.delay_time as u64 * 10)
+    }
+
+    pub fn disposal_method(&self) -> DisposalMethod {
+        self.packed_fields.disposal_method
+    }
+
+    /// Replaces the Transparent Color Index, leaving the Transparency Flag as it is.
+    pub(crate) fn set_transparent_color_index(&mut self, transparent_color_index: u8) {
+        self.transparent_color_index = transparent_color_index;
+    }
+
+    pub(crate) fn set_delay_time(&mut self, delay_time: u16) {
+        self.delay_time = delay_time;
+    }
+
+    pub(crate) fn set_disposal_method(&mut self, disposal_method: DisposalMethod) {
+        self.packed_fields.disposal_method = disposal_method;
+    }
+
+    /// Control for a stand-in frame that only draws `transparent_color_index`: it keeps the delay and user input
+    /// of `graphic_control_extension` but is never disposed of, so the canvas stays untouched.
+    pub(crate) fn transparent_placeholder(
+        graphic_control_extension: Option<&Self>,
+        transparent_color_index: u8,
+    ) -> Self {
+        let (reserved, user_input_flag, delay_time) =
+            graphic_control_extension.map_or((0, false, 0), |graphic_control_extension| {
+                (
+                    graphic_control_extension.packed_fields.reserved,
+                    graphic_control_extension.packed_fields.user_input_flag,
+                    graphic_control_extension.delay_time,
+                )
+            });
+        GraphicControlExtension {
+            packed_fields: GCEPackedFields {
+                reserved,
+                disposal_method: DisposalMethod::DoNotDispose,
+                user_input_flag,
+                transparent_color_flag: true,
+            },
+            delay_time,
+            transparent_color_index,
+        }
+    }
+
+    /// The reserved bits of the packed fields, which must be 0.
+    pub(crate) fn reserved(&self) -> u8 {
+        self.packed_fields.reserved
+    }
+
+    /// The Transparent Color Index, only when the Transparency Flag is set.
+    pub fn transparent_color_index(&self) -> Option<u8> {
+        self.packed_fields
+            .transparent_color_flag
+            .then_some(self.transparent_color_index)
+    }
+}
+
+/// The Comment Extension contains textual information which is not part of the actual graphics in the GIF Data Stream.
+/// It is suitable for including comments about the graphics, credits, descriptions or any other type of non-control and non-graphic data.
+/// This block does not have scope. This block cannot be modified by any extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CommentExtension {
+    /// Comment Data - the concatenated payload of the data sub-blocks, intended to be 7-bit ASCII text.
+    comment_data: Vec<u8>,
+
+    /// Number of data sub-blocks the data was split into in the Data Stream.
+    sub_block_count: usize,
+
+    /// Total size of the data sub-block payloads in the Data Stream, without their size bytes.
+    data_size: usize,
+
+    /// Index of the image this block came before in the Data Stream, the number of images when it follows the last one.
+    /// Set by the decoder; a block parsed on its own stands in front of image 0.
+    pub(crate) before_frame: usize,
+}
+
+impl GifBlock for CommentExtension {
+    /// Parses the extension body; the Extension Introducer and Comment Label are already consumed.
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        let (comment_data, sub_block_count, data_size) = read_counted_sub_blocks(buf, false)?;
+        Ok(CommentExtension {
+            comment_data,
+            sub_block_count,
+            data_size,
+            before_frame: 0,
+        })
+    }
+}
+
+impl EncodeBlock for CommentExtension {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&[EXTENSION_INTRODUCER, COMMENT_LABEL]);
+        encoder::write_data_sub_blocks(out, &self.comment_data);
+    }
+}
+
+impl CommentExtension {
+    pub fn comment_data(&self) -> &[u8] {
+        &self.comment_data
+    }
+
+    /// Number of data sub-blocks the data was split into in the Data Stream.
+    pub fn sub_block_count(&self) -> usize {
+        self.sub_block_count
+    }
+
+    /// Total size of the data sub-block payloads in the Data Stream, without their size bytes.
+    pub fn data_size(&self) -> usize {
+        self.data_size
+    }
+
+    /// The Comment Data as text. The spec recommends 7-bit ASCII but nothing enforces it,
+    /// so bytes that are not valid UTF-8 become replacement characters instead of an error.
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.comment_data)
+    }
+
+    /// Index of the image this extension came before in the Data Stream, the frame count when it follows the last image.
+    pub fn before_frame(&self) -> usize {
+        self.before_frame
+    }
+}
+
+/// The Plain Text Extension contains textual data and the parameters necessary to render that data as a graphic, in a simple form.
+/// Text data are rendered using a grid of character cells defined by the parameters in the block fields.
+/// The scope of this block is the Plain Text Data Block contained in it. This block may be modified by the Graphic Control Extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PlainTextExtension {
+    /// The Graphic Control Extension in front of this block, which applies to it rather than to the next image.
+    pub(crate) graphic_control_extension: Option<GraphicControlExtension>,
+
+    /// Text Grid Left Position - Column number, in pixels, of the left edge of the text grid, with respect to the left edge of the Logical Screen.
+    text_grid_left_position: u16,
+
+    /// Text Grid Top Position - Row number, in pixels, of the top edge of the text grid, with respect to the top edge of the Logical Screen.
+    text_grid_top_position: u16,
+
+    /// Image Grid Width - Width of the text grid in pixels.
+    text_grid_width: u16,
+
+    /// Image Grid Height - Height of the text grid in pixels.
+    text_grid_height: u16,
+
+    /// Character Cell Width - Width, in pixels, of each cell in the grid.
+    character_cell_width: u8,
+
+    /// Character Cell Height - Height, in pixels, of each cell in the grid.
+    character_cell_height: u8,
+
+    /// Text Foreground Color Index - Index into the Global Color Table to be used to render the text foreground.
+    text_foreground_color_index: u8,
+
+    /// Text Background Color Index - Index into the Global Color Table to be used to render the text background.
+    text_background_color_index: u8,
+
+    /// Plain Text Data - the concatenated payload of the data sub-blocks.
+    plain_text_data: Vec<u8>,
+
+    /// Number of data sub-blocks the data was split into in the Data Stream.
+    sub_block_count: usize,
+
+    /// Total size of the data sub-block payloads in the Data Stream, without their size bytes.
+    data_size: usize,
+
+    /// Index of the image this block came before in the Data Stream, the number of images when it follows the last one.
+    /// Set by the decoder; a block parsed on its own stands in front of image 0.
+    pub(crate) before_frame: usize,
+}
+
+impl GifBlock for PlainTextExtension {
+    /// Parses the extension body; the Extension Introducer and Plain Text Label are already consumed.
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        let block_size: u8 = buf.read_u8()?;
+        let mark = buf.mark();
+        let text_grid_left_position = buf.read_le_u16()?;
+        let text_grid_top_position = buf.read_le_u16()?;
+        let text_grid_width = buf.read_le_u16()?;
+        let text_grid_height = buf.read_le_u16()?;
+        let character_cell_width = buf.read_u8()?;
+        let character_cell_height = buf.read_u8()?;
+        let text_foreground_color_index = buf.read_u8()?;
+        let text_background_color_index = buf.read_u8()?;
+        check_block_size(buf, mark, block_size)?;
+        let (plain_text_data, sub_block_count, data_size) = read_counted_sub_blocks(buf, false)?;
+
+        Ok(PlainTextExtension {
+            graphic_control_extension: None,
+            text_grid_left_position,
+            text_grid_top_position,
+            text_grid_width,
+            text_grid_height,
+            character_cell_width,
+            character_cell_height,
+            text_foreground_color_index,
+            text_background_color_index,
+            plain_text_data,
+            sub_block_count,
+            data_size,
+            before_frame: 0,
+        })
+    }
+}
+
+impl EncodeBlock for PlainTextExtension {
+    fn encode(&self, out: &mut Vec<u8>) {
+        if let Some(graphic_control_extension) = &self.graphic_control_extension {
+            graphic_control_extension.encode(out);
+        }
+        out.extend_from_slice(&[EXTENSION_INTRODUCER, PLAIN_TEXT_LABEL, 12]);
+        for value in [
+            self.text_grid_left_position,
+            self.text_grid_top_position,
+            self.text_grid_width,
+            self.text_grid_height,
+        ] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        out.extend_from_slice(&[
+            self.character_cell_width,
+            self.character_cell_height,
+            self.text_foreground_color_index,
+            self.text_background_color_index,
+        ]);
+        encoder::write_data_sub_blocks(out, &self.plain_text_data);
+    }
+}
+
+impl PlainTextExtension {
+    pub fn graphic_control_extension(&self) -> Option<&GraphicControlExtension> {
+        self.graphic_control_extension.as_ref()
+    }
+
+    /// Placement of the text grid on the Logical Screen as `(left, top, width, height)`.
+    pub fn text_grid(&self) -> (u16, u16, u16, u16) {
+        (
+            self.text_grid_left_position,
+            self.text_grid_top_position,
+            self.text_grid_width,
+            self.text_grid_height,
+        )
+    }
+
+    /// Size of each character cell as `(width, height)`.
+    pub fn character_cell(&self) -> (u8, u8) {
+        (self.character_cell_width, self.character_cell_height)
+    }
+
+    pub fn text_foreground_color_index(&self) -> u8 {
+        self.text_foreground_color_index
+    }
+
+    pub fn text_background_color_index(&self) -> u8 {
+        self.text_background_color_index
+    }
+
+    pub fn plain_text_data(&self) -> &[u8] {
+        &self.plain_text_data
+    }
+
+    /// Number of data sub-blocks the data was split into in the Data Stream.
+    pub fn sub_block_count(&self) -> usize {
+        self.sub_block_count
+    }
+
+    /// Total size of the data sub-block payloads in the Data Stream, without their size bytes.
+    pub fn data_size(&self) -> usize {
+        self.data_size
+    }
+
+    /// Index of the image this extension came before in the Data Stream, the frame count when it follows the last image.
+    pub fn before_frame(&self) -> usize {
+        self.before_frame
+    }
+}
+
+/// The Application Extension contains application-specific information; it conforms with the extension block syntax, and its block label is 0xFF.
+/// This block does not have scope. This block cannot be modified by any extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ApplicationExtension {
+    /// Application Identifier - Sequence of eight printable ASCII characters used to identify the application owning the Application Extension.
+    application_identifier: [u8; 8],
+
+    /// Application Authentication Code - Sequence of three bytes used to authenticate the Application Identifier.
+    application_authentication_code: [u8; 3],
+
+    /// Application Data - the concatenated payload of the data sub-blocks.
+    /// XMP packets are not sub-blocked, so for them this is the raw data including the size bytes and the magic trailer.
+    application_data: Vec<u8>,
+
+    /// Number of data sub-blocks the data was split into in the Data Stream.
+    sub_block_count: usize,
+
+    /// Total size of the data sub-block payloads in the Data Stream, without their size bytes.
+    data_size: usize,
+
+    /// Index of the image this block came before in the Data Stream, the number of images when it follows the last one.
+    /// Set by the decoder; a block parsed on its own stands in front of image 0.
+    pub(crate) before_frame: usize,
+}
+
+impl GifBlock for ApplicationExtension {
+    /// Parses the extension body; the Extension Introducer and Extension Label are already consumed.
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        let block_size: u8 = buf.read_u8()?;
+        let mark = buf.mark();
+        let mut application_identifier = [0u8; 8];
+        buf.read_exact(&mut application_identifier)?;
+        let mut application_authentication_code = [0u8; 3];
+        buf.read_exact(&mut application_authentication_code)?;
+        check_block_size(buf, mark, block_size)?;
+        let (application_data, sub_block_count, data_size) = read_counted_sub_blocks(
+            buf,
+            is_xmp(&application_identifier, &application_authentication_code),
+        )?;
+
+        Ok(ApplicationExtension {
+            application_identifier,
+            application_authentication_code,
+            application_data,
+            sub_block_count,
+            data_size,
+            before_frame: 0,
+        })
+    }
+}
+
+impl EncodeBlock for ApplicationExtension {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&[EXTENSION_INTRODUCER, APPLICATION_EXTENSION_LABEL, 11]);
+        out.extend_from_slice(&self.application_identifier);
+        out.extend_from_slice(&self.application_authentication_code);
+        if is_xmp(
+            &self.application_identifier,
+            &self.application_authentication_code,
+        ) {
+            // the raw data already carries its own size bytes, only the Block Terminator is missing
+            out.extend_from_slice(&self.application_data);
+            out.push(0);
+        } else {
+            encoder::write_data_sub_blocks(out, &self.application_data);
+        }
+    }
+}
+
+impl ApplicationExtension {
+    pub fn application_identifier(&self) -> &[u8; 8] {
+        &self.application_identifier
+    }
+
+    pub fn application_authentication_code(&self) -> &[u8; 3] {
+        &self.application_authentication_code
+    }
+
+    pub fn application_data(&self) -> &[u8] {
+        &self.application_data
+    }
+
+    /// Number of data sub-blocks the data was split into in the Data Stream.
+    pub fn sub_block_count(&self) -> usize {
+        self.sub_block_count
+    }
+
+    /// Total size of the data sub-block payloads in the Data Stream, without their size bytes.
+    pub fn data_size(&self) -> usize {
+        self.data_size
+    }
+
+    /// Index of the image this extension came before in the Data Stream, the frame count when it follows the last image.
+    pub fn before_frame(&self) -> usize {
+        self.before_frame
+    }
+
+    /// Loop count of the `NETSCAPE2.0` (or `ANIMEXTS1.0`) looping extension; 0 means loop forever.
+    /// Its data is a sub-block id of 1 followed by the loop count as an unsigned little endian u16.
+    pub fn netscape_loop_count(&self) -> Option<u16> {
+        let is_looping_extension = matches!(
+            (
+                &self.application_identifier,
+                &self.application_authentication_code
+            ),
+            (b"NETSCAPE", b"2.0") | (b"ANIMEXTS", b"1.0")
+        );
+        match self.application_data[..] {
+            [1, low, high, ..] if is_looping_extension => Some(u16::from_le_bytes([low, high])),
+            _ => None,
+        }
+    }
+
+    /// The XML packet of an `XMP Data` extension, without the magic trailer.
+    pub fn xmp(&self) -> Option<&str> {
+        if !is_xmp(
+            &self.application_identifier,
+            &self.application_authentication_code,
+        ) {
+            return None;
+        }
+        let packet = self
+            .application_data
+            .strip_suffix(&XMP_MAGIC_TRAILER)
+            .unwrap_or(&self.application_data);
+        std::str::from_utf8(packet).ok()
+    }
+
+    /// The ICC color profile of an `ICCRGBG1012` extension, split over its data sub-blocks like any other payload.
+    pub fn icc_profile(&self) -> Option<&[u8]> {
+        (&self.application_identifier == b"ICCRGBG1"
+            && &self.application_authentication_code == b"012")
+            .then_some(&self.application_data[..])
+    }
+}
+
+/// XMP packets are written as raw bytes after the Application Authentication Code.
+/// A decoder reading them as sub-blocks treats every byte as a size; the trailer
+/// `0x01, 0xFF, 0xFE, .., 0x01, 0x00` makes every such jump land on the Block Terminator after it.
+const XMP_MAGIC_TRAILER: [u8; 257] = {
+    let mut trailer = [0u8; 257];
+    trailer[0] = 1;
+    let mut index = 1;
+    while index < trailer.len() {
+        trailer[index] = (256 - index) as u8;
+        index += 1;
+    }
+    trailer
+};
+
+fn is_xmp(application_identifier: &[u8; 8], application_authentication_code: &[u8; 3]) -> bool {
+    application_identifier == b"XMP Data" && application_authentication_code == b"XMP"
+}
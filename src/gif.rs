@@ -1,32 +1,123 @@
-use crate::parser::GifBuffer;
+use crate::animation::Animation;
+use crate::encoder::{self, EncodeBlock, GifEncoder};
+use crate::error::GifError;
+use crate::extension::{
+    ApplicationExtension, CommentExtension, DisposalMethod, GraphicControlExtension,
+    PlainTextExtension, APPLICATION_EXTENSION_LABEL, COMMENT_LABEL, GRAPHIC_CONTROL_LABEL,
+    PLAIN_TEXT_LABEL,
+};
+use crate::lzw;
+use crate::options::{DecodeOptions, PaletteMode};
+use crate::palette::Palette;
+use crate::parser::{ByteSource, GifBlock, GifBuffer};
+use crate::render::{self, Canvas, Compositor};
+use crate::warning::GifWarning;
 use std::fmt;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::Write;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 
-#[derive(Debug, PartialEq, Eq)]
+/// The start of every signature, "GIF" followed by the first digit of the version.
+const GIF_MAGIC: &[u8] = b"GIF8";
+/// Identifies the beginning of an extension block.
+pub(crate) const EXTENSION_INTRODUCER: u8 = 0x21;
+/// Identifies the beginning of an Image Descriptor.
+const IMAGE_SEPARATOR: u8 = 0x2C;
+/// Indicates the end of the GIF Data Stream.
+const TRAILER: u8 = 0x3B;
+
+/// Reads the fixed byte that introduces a block, failing if something else is found.
+fn expect_byte<S: ByteSource + ?Sized>(buf: &mut S, expected: u8) -> Result<(), GifError> {
+    let offset = buf.position();
+    let found = buf.read_u8()?;
+    if found != expected {
+        return Err(GifError::UnexpectedByte {
+            expected,
+            found,
+            offset,
+        });
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct GifSignature {
-    magic: String,
+    /// Always `GIF`, the Signature is matched case-insensitively and kept uppercase.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_magic"))]
+    magic: [u8; 3],
     version: GifVersion,
 }
 
+impl GifBlock for GifSignature {
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        let offset = buf.position();
+        let mut magic = [0u8; 3];
+        buf.read_exact(&mut magic)?;
+        check_utf8(&magic)?;
+        magic.make_ascii_uppercase();
+        if &magic != b"GIF" {
+            return Err(GifError::InvalidSignature {
+                found: String::from_utf8_lossy(&magic).into_owned(),
+                offset,
+            });
+        }
+        let offset = buf.position();
+        let mut version = [0u8; 3];
+        buf.read_exact(&mut version)?;
+        check_utf8(&version)?;
+        let version = match &version {
+            b"89a" => GifVersion::GIF89a,
+            b"87a" => GifVersion::GIF87a,
+            version => {
+                return Err(GifError::UnknownVersion {
+                    version: String::from_utf8_lossy(version).into_owned(),
+                    offset,
+                })
+            }
+        };
+        Ok(GifSignature { magic, version })
+    }
+}
+
+/// Fails with `GifError::Utf8` when the header text `bytes` is not valid UTF-8, e.g. for a PNG file.
+/// Only that failure allocates, for the `FromUtf8Error` the error carries.
+fn check_utf8(bytes: &[u8]) -> Result<(), GifError> {
+    if std::str::from_utf8(bytes).is_err() {
+        String::from_utf8(bytes.to_vec())?;
+    }
+    Ok(())
+}
+
 impl GifSignature {
-    pub fn parse(buf: &mut GifBuffer) -> Self {
-        let magic = String::from_utf8(buf.read_slice(3))
-            .map_err(|err| {
-                eprintln!("ERROR: Unable to read magic value: {err}");
-            })
-            .unwrap()
-            .to_uppercase();
-        assert_eq!("GIF", &magic, "ERROR: Expected 'GIF'  but found '{magic}'");
-        let version = String::from_utf8(buf.read_slice(3))
-            .map_err(|err| {
-                eprintln!("ERROR: Unable to read gif version: {err}");
-            })
-            .unwrap()
-            .into();
-        GifSignature { magic, version }
+    pub fn magic(&self) -> &str {
+        // only ever holds the validated ASCII "GIF"
+        std::str::from_utf8(&self.magic).unwrap_or("GIF")
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Writes the Signature as the string `"GIF"`, like [`GifSignature::magic`], instead of a byte array.
+#[cfg(feature = "serde")]
+fn serialize_magic<S: serde::Serializer>(
+    magic: &[u8; 3],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(std::str::from_utf8(magic).unwrap_or("GIF"))
+}
+
+impl EncodeBlock for GifSignature {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.magic);
+        out.extend_from_slice(String::from(self.version).as_bytes());
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum GifVersion {
     GIF89a,
     GIF87a,
@@ -52,6 +143,21 @@ impl From<String> for GifVersion {
     }
 }
 
+impl FromStr for GifVersion {
+    type Err = GifError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "89a" => Ok(GifVersion::GIF89a),
+            "87a" => Ok(GifVersion::GIF87a),
+            _ => Err(GifError::UnknownVersion {
+                version: value.to_string(),
+                offset: 0,
+            }),
+        }
+    }
+}
+
 impl From<&str> for GifVersion {
     fn from(value: &str) -> Self {
         match value {
@@ -61,7 +167,8 @@ impl From<&str> for GifVersion {
         }
     }
 }
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LSDPackedFields {
     /// Flag indicating the presence of a Global Color Table; if the flag is set, the Global Color Table will immediately follow the Logical Screen Descriptor.
     /// This flag also selects the interpretation of the Background Color Index; if the flag is set, the value of the Background Color Index field should be used as the table index of the background color.
@@ -92,25 +199,43 @@ pub struct LSDPackedFields {
     /// Even if there is no Global Color Table specified, set this field according to the above formula so that decoders can choose the best graphics mode to display the stream in.
     /// (This field is made up of the 3 least significant bits of the byte.)
     global_color_table_size: u8,
+
+    /// The packed byte as read, which the fields above are decoded from.
+    raw: u8,
 }
 
-impl LSDPackedFields {
-    pub fn parse(buf: &mut GifBuffer) -> Self {
-        let m_u8 = buf.read_u8();
+impl GifBlock for LSDPackedFields {
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        let m_u8 = buf.read_u8()?;
         let global_color_table_flag = (m_u8 >> 7) & 0b1 == 1;
         let color_resolution = ((m_u8 >> 4) & 0b111) + 1u8;
         let sort_flag = (m_u8 >> 3) & 0b1 == 1;
         let global_color_table_size = (m_u8 & 0b111) + 1_u8;
-        LSDPackedFields {
+        Ok(LSDPackedFields {
             global_color_table_flag,
             color_resolution,
             sort_flag,
             global_color_table_size,
-        }
+            raw: m_u8,
+        })
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl EncodeBlock for LSDPackedFields {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.raw);
+    }
+}
+
+impl LSDPackedFields {
+    /// The packed byte exactly as it appeared in the Logical Screen Descriptor.
+    pub fn raw(&self) -> u8 {
+        self.raw
+    }
+}
+
+#[derive(Debug, Clone, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LogicalScreenDescriptor {
     /// Raster width in pixels (LSB first)
     /// Logical Screen Width - Width, in pixels, of the Logical Screen where the images will be rendered in the displaying device.
@@ -130,29 +255,85 @@ pub struct LogicalScreenDescriptor {
     background_color_index: u8,
 
     /// Pixel Aspect Ratio - Factor used to compute an approximation of the aspect ratio of the pixel in the original image.  If the value of the field is not 0, this approximation of the aspect ratio is computed based on the formula:
-    ///	Aspect Ratio = (Pixel Aspect Ratio + 15) / 64
+    ///    Aspect Ratio = (Pixel Aspect Ratio + 15) / 64
     /// The Pixel Aspect Ratio is defined to be the quotient of the pixel's width over its height.  The value range in this field allows specification of the widest pixel of 4:1 to the tallest pixel of 1:4 in increments of 1/64th.
 
     /// Values : 0      -   No aspect ratio information is given.
     ///          1..255 -   Value used in the computation.
     pixel_aspect_ratio: u8,
+
+    /// Where the descriptor starts in the input, reported by `ImageTooLarge` when its screen can't be allocated.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) offset: usize,
+}
+// where the descriptor was read from takes no part in equality, like the offsets of errors
+impl PartialEq for LogicalScreenDescriptor {
+    fn eq(&self, other: &Self) -> bool {
+        self.logical_screen_width == other.logical_screen_width
+            && self.logical_screen_height == other.logical_screen_height
+            && self.packed_fields == other.packed_fields
+            && self.background_color_index == other.background_color_index
+            && self.pixel_aspect_ratio == other.pixel_aspect_ratio
+    }
 }
 
-impl LogicalScreenDescriptor {
-    pub fn parse(buf: &mut GifBuffer) -> Self {
-        let logical_screen_width = buf.read_le_u16();
-        let logical_screen_height = buf.read_le_u16();
-        let packed_fields = LSDPackedFields::parse(buf);
-        let background_color_index = buf.read_u8();
-        let pixel_aspect_ratio = buf.read_u8();
-
-        LogicalScreenDescriptor {
+impl Hash for LogicalScreenDescriptor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.logical_screen_width.hash(state);
+        self.logical_screen_height.hash(state);
+        self.packed_fields.hash(state);
+        self.background_color_index.hash(state);
+        self.pixel_aspect_ratio.hash(state);
+    }
+}
+
+impl GifBlock for LogicalScreenDescriptor {
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        let offset = buf.position();
+        let logical_screen_width = buf.read_le_u16()?;
+        let logical_screen_height = buf.read_le_u16()?;
+        let packed_fields = LSDPackedFields::parse(buf)?;
+        let background_color_index = buf.read_u8()?;
+        let pixel_aspect_ratio = buf.read_u8()?;
+
+        Ok(LogicalScreenDescriptor {
             logical_screen_width,
             logical_screen_height,
             packed_fields,
             background_color_index,
             pixel_aspect_ratio,
-        }
+            offset,
+        })
+    }
+}
+
+impl EncodeBlock for LogicalScreenDescriptor {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.logical_screen_width.to_le_bytes());
+        out.extend_from_slice(&self.logical_screen_height.to_le_bytes());
+        self.packed_fields.encode(out);
+        out.push(self.background_color_index);
+        out.push(self.pixel_aspect_ratio);
+    }
+}
+
+impl LogicalScreenDescriptor {
+    pub fn logical_screen_width(&self) -> u16 {
+        self.logical_screen_width
+    }
+
+    pub fn logical_screen_height(&self) -> u16 {
+        self.logical_screen_height
+    }
+
+    pub fn packed_fields(&self) -> &LSDPackedFields {
+        &self.packed_fields
+    }
+
+    /// Width over height of a pixel, `Aspect Ratio = (Pixel Aspect Ratio + 15) / 64`.
+    /// `None` when the field is 0 and no aspect ratio information is given.
+    pub fn pixel_aspect_ratio(&self) -> Option<f32> {
+        (self.pixel_aspect_ratio != 0).then(|| (self.pixel_aspect_ratio as f32 + 15.0) / 64.0)
     }
 }
 
@@ -165,86 +346,96 @@ impl LogicalScreenDescriptor {
 /// Its presence is marked by the Global Color Table Flag being set to 1 in the Logical Screen Descriptor; if present, it immediately follows the Logical Screen Descriptor and contains a number of bytes equal to
 /// `3 x 2^(Size of Global Color Table+1)`
 /// This block is OPTIONAL; at most one Global Color Table may be present per Data Stream.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct GlobalColorMap {
-    /// sequentual vector of (r, g, b) values n times
-    intensities: Vec<u8>,
-    /// size of intensities(r,g,b)
-    size: usize,
+    palette: Palette,
 }
 
 impl GlobalColorMap {
-    pub fn parse(buf: &mut GifBuffer, screen_descriptor: &LogicalScreenDescriptor) -> Option<Self> {
+    pub fn parse(
+        buf: &mut GifBuffer,
+        screen_descriptor: &LogicalScreenDescriptor,
+    ) -> Result<Option<Self>, GifError> {
         if !screen_descriptor.packed_fields.global_color_table_flag {
-            return None;
+            return Ok(None);
         }
 
         let pixel = screen_descriptor.packed_fields.global_color_table_size;
-        let size: usize = 3 * 2_usize.pow(pixel as u32);
+        let palette = Palette::parse(buf, 2_usize.pow(pixel as u32))?;
 
-        let mut intensities: Vec<u8> = vec![0u8; size];
+        Ok(Some(GlobalColorMap { palette }))
+    }
 
-        for i in 0..intensities.len() {
-            // intensities[i] = buf.read_u8();
-            intensities[i] =
-                ((buf.read_u8() as u32 * 255_u32) / ((1_u32 << pixel as u32) - 1_u32)) as u8;
-        }
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// The table as `[r, g, b]` entries, in color index order.
+    pub fn as_rgb_triples(&self) -> Vec<[u8; 3]> {
+        self.palette.entries().to_vec()
+    }
+
+    /// The table as the flat `r, g, b, r, g, b, ...` bytes of a PNG `PLTE` chunk, for indexed PNG export.
+    pub fn to_png_palette(&self) -> Vec<u8> {
+        self.palette.entries().as_flattened().to_vec()
+    }
+
+    /// See [`Palette::nearest_index`].
+    pub fn nearest_index(&self, rgb: (u8, u8, u8)) -> u8 {
+        self.palette.nearest_index(rgb)
+    }
+
+    /// The `[r, g, b]` entries in color index order.
+    pub fn iter(&self) -> std::slice::Iter<'_, [u8; 3]> {
+        self.palette.entries().iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a GlobalColorMap {
+    type Item = &'a [u8; 3];
+    type IntoIter = std::slice::Iter<'a, [u8; 3]>;
 
-        Some(GlobalColorMap { intensities, size })
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
+impl EncodeBlock for GlobalColorMap {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.palette.encode(out);
+    }
+}
+
+/// Number of colors shown at each end of the table by `GlobalColorMap`'s `Display`.
+const DISPLAYED_COLORS: usize = 3;
+
 impl fmt::Display for GlobalColorMap {
+    /// Lists every color of small tables; larger ones show the first and last few colors around an ellipsis.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
             "Global Color Map: \n  {{size: {size}}}\n",
-            size = self.size
+            size = self.palette.len() * 3
         )?;
-        for index in 0..9 {
-            let mut prefex = "";
-            if index % 3 == 0 {
-                write!(f, "\n  {index:#03} => [")?;
-            } else {
-                prefex = ", ";
+        let len = self.palette.len();
+        for (index, [r, g, b]) in self.iter().enumerate() {
+            if len > 2 * DISPLAYED_COLORS && index == DISPLAYED_COLORS {
+                write!(f, "\n    ...")?;
             }
-            write!(
-                f,
-                "{prefex}{intensity:#04x}",
-                intensity = self.intensities[index]
-            )?;
-            if index % 3 == 2 {
-                write!(f, "]")?;
-            }
-        }
-        for _ in 0..3 {
-            write!(f, "\n    ...")?;
-        }
-        let len = self.intensities.len();
-        for index in (0..9).rev() {
-            let mut prefex = "";
-            let index = len - index - 1;
-
-            if index % 3 == 0 {
-                write!(f, "\n  {index:#03} => [")?;
-            } else {
-                prefex = ", ";
-            }
-
-            write!(
-                f,
-                "{prefex}{intensity:#04x}",
-                intensity = self.intensities[index]
-            )?;
-            if index % 3 == 2 {
-                write!(f, "]")?;
+            if len > 2 * DISPLAYED_COLORS
+                && (DISPLAYED_COLORS..len - DISPLAYED_COLORS).contains(&index)
+            {
+                continue;
             }
+            write!(f, "\n  {index:#03} => [{r:#04x}, {g:#04x}, {b:#04x}]")?;
         }
         Ok(())
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct IDPackedFields {
     //  M=0 - Use global color map, ignore 'pixel'
     //  M=1 - Local color map follows, use 'pixel'
@@ -265,33 +456,50 @@ pub struct IDPackedFields {
     ///         1 -   Ordered by decreasing importance, most important color first.
     sort_flag: bool,
 
-    /// Size of Local Color Table - If the Local Color Table Flag is set to 1, the value in this field is used to calculate the number of bytes contained in the Local Color Table. To determine that actual size of the color table, raise 2 to the value of the field + 1. This value should be 0 if there is no Local Color Table specified. (This field is made up of the 3 least significant bits of the byte.)
+    /// Reserved - Bits 3 and 4 of the packed byte, reserved for future use and must be 0.
     reserved: u8,
 
     // pixel+1 - # bits per pixel for this image
-    /// Extensions and Scope. The scope of this block is the Table-based Image Data Block that follows it. This block may be modified by the Graphic Control Extension.
+    /// Size of Local Color Table - If the Local Color Table Flag is set to 1, the value in this field is used to calculate the number of bytes contained in the Local Color Table. To determine that actual size of the color table, raise 2 to the value of the field + 1. This value should be 0 if there is no Local Color Table specified. (This field is made up of the 3 least significant bits of the byte.)
     local_color_table_size: u8,
+
+    /// The packed byte as read, which the fields above are decoded from.
+    raw: u8,
 }
 
 // 0 1 2 3 4 5 6 7
 // 7 6 5 4 3 2 1 0
 
-impl IDPackedFields {
-    pub fn parse(buf: &mut GifBuffer) -> Self {
-        let m_u8: u8 = buf.read_u8();
+impl GifBlock for IDPackedFields {
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        let m_u8: u8 = buf.read_u8()?;
         let local_color_table_flag = (m_u8 >> 7) & 0b1 == 1;
         let interlace_flag = (m_u8 >> 6) & 0b1 == 1;
         let sort_flag = (m_u8 >> 5) & 0b1 == 1;
-        let reserved = (m_u8 >> 4) & 0b11;
+        let reserved = (m_u8 >> 3) & 0b11;
         let local_color_table_size: u8 = (m_u8 & 0b111) + 1_u8;
 
-        IDPackedFields {
+        Ok(IDPackedFields {
             local_color_table_flag,
             interlace_flag,
             sort_flag,
             reserved,
             local_color_table_size,
-        }
+            raw: m_u8,
+        })
+    }
+}
+
+impl EncodeBlock for IDPackedFields {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.raw);
+    }
+}
+
+impl IDPackedFields {
+    /// The packed byte exactly as it appeared in the Image Descriptor.
+    pub fn raw(&self) -> u8 {
+        self.raw
     }
 }
 
@@ -303,7 +511,8 @@ impl IDPackedFields {
 /// This block is REQUIRED for an image.
 /// Exactly one Image Descriptor must be present per image in the Data Stream.
 /// An unlimited number of images may be present per Data Stream.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ImageDescriptor {
     /// Identifies the beginning of an Image Descriptor. This field contains the fixed value 0x2C.
     // Start of image in pixels from the left side of the screen (LSB first);
@@ -323,120 +532,1887 @@ pub struct ImageDescriptor {
     image_height: u16,
 
     packed_fields: IDPackedFields,
+
+    /// Where the descriptor starts in the input, reported by `Gif::validate` for a zero-sized frame.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) offset: usize,
+}
+// where the descriptor was read from takes no part in equality, like the offsets of errors
+impl PartialEq for ImageDescriptor {
+    fn eq(&self, other: &Self) -> bool {
+        self.rect() == other.rect() && self.packed_fields == other.packed_fields
+    }
+}
+
+impl Eq for ImageDescriptor {}
+
+impl GifBlock for ImageDescriptor {
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        let offset = buf.position();
+        expect_byte(buf, IMAGE_SEPARATOR)?;
+        let image_left = buf.read_le_u16()?;
+        let image_top = buf.read_le_u16()?;
+        let image_width = buf.read_le_u16()?;
+        let image_height = buf.read_le_u16()?;
+        if image_width == 0 || image_height == 0 {
+            return Err(GifError::ZeroSizedFrame { offset });
+        }
+        let packed_fields = IDPackedFields::parse(buf)?;
+        Ok(ImageDescriptor {
+            image_left,
+            image_top,
+            image_width,
+            image_height,
+            packed_fields,
+            offset,
+        })
+    }
 }
+
+impl EncodeBlock for ImageDescriptor {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(IMAGE_SEPARATOR);
+        for value in [
+            self.image_left,
+            self.image_top,
+            self.image_width,
+            self.image_height,
+        ] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        self.packed_fields.encode(out);
+    }
+}
+
 impl ImageDescriptor {
-    pub fn parse(buf: &mut GifBuffer) -> Self {
-        let image_separator: u8 = buf.read_u8();
-        assert_eq!(
-            0x2C, image_separator,
-            "ERROR: Expected \",\" or \"0x2C\" but found {image_separator:#04x}"
-        );
-        let image_left = buf.read_le_u16();
-        let image_top = buf.read_le_u16();
-        let image_width = buf.read_le_u16();
-        let image_height = buf.read_le_u16();
-        let packed_fields = IDPackedFields::parse(buf);
+    /// `(image_left, image_top, image_width, image_height)`
+    pub(crate) fn rect(&self) -> (u16, u16, u16, u16) {
+        (
+            self.image_left,
+            self.image_top,
+            self.image_width,
+            self.image_height,
+        )
+    }
+
+    /// A copy of this descriptor placed at `(image_left, image_top, image_width, image_height)`, keeping its packed fields.
+    pub(crate) fn with_rect(
+        &self,
+        (image_left, image_top, image_width, image_height): (u16, u16, u16, u16),
+    ) -> Self {
         ImageDescriptor {
             image_left,
             image_top,
             image_width,
             image_height,
-            packed_fields,
+            ..self.clone()
         }
     }
+
+    pub fn packed_fields(&self) -> &IDPackedFields {
+        &self.packed_fields
+    }
+
+    /// Number of pixels in the image, computed in `usize` since `image_width * image_height` overflows `u16`.
+    pub fn area(&self) -> usize {
+        self.image_width as usize * self.image_height as usize
+    }
+
+    /// Number of bytes needed to hold the image with `channels` bytes per pixel, e.g. 3 for RGB or 4 for RGBA.
+    pub fn byte_size(&self, channels: usize) -> usize {
+        self.area() * channels
+    }
 }
 
 /// This block contains a color table, which is a sequence of bytes representing red-green-blue color triplets. The Local Color Table is used by the image that immediately follows. Its presence is marked by the Local Color Table Flag being set to 1 in the Image Descriptor; if present, the Local Color Table immediately follows the Image Descriptor and contains a number of bytes equal to
 ///    `3x2^(Size of Local Color Table+1)`
 ///If present, this color table temporarily becomes the active color table and the following image should be processed using it. This block is OPTIONAL; at most one Local Color Table may be present per Image Descriptor and its scope is the single image associated with the Image Descriptor that precedes it.
-#[derive(Debug, PartialEq, Eq)]
-pub struct LocalColorMap {}
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LocalColorMap {
+    palette: Palette,
+}
 impl LocalColorMap {
-    pub fn parse(_buf: &mut GifBuffer, image_descriptor: &ImageDescriptor) -> Option<Self> {
+    pub fn parse(
+        buf: &mut GifBuffer,
+        image_descriptor: &ImageDescriptor,
+    ) -> Result<Option<Self>, GifError> {
         if !image_descriptor.packed_fields.local_color_table_flag {
-            return None;
+            return Ok(None);
         }
 
-        Some(LocalColorMap {})
+        let pixel = image_descriptor.packed_fields.local_color_table_size;
+        let palette = Palette::parse(buf, 2_usize.pow(pixel as u32))?;
+
+        Ok(Some(LocalColorMap { palette }))
+    }
+
+    pub fn palette(&self) -> &Palette {
+        &self.palette
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub struct RasterData {}
+impl EncodeBlock for LocalColorMap {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.palette.encode(out);
+    }
+}
+
+/// The image data for table based images consists of a sequence of sub-blocks, of size at most 255 bytes each, containing an index into the active color table, for each pixel in the image.
+/// Pixel indices are in order of left to right and from top to bottom.
+/// Each index must be within the range of the size of the active color table, starting at 0.
+/// The sequence of indices is encoded using the LZW Algorithm with variable-length code, as described in Appendix F.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RasterData {
+    /// LZW Minimum Code Size - This byte determines the initial number of bits used for LZW codes in the image data, as described in Appendix F.
+    lzw_minimum_code_size: u8,
+
+    /// Decoded color table index of every pixel, left to right and top to bottom.
+    /// Interlaced images are already put back into sequential row order.
+    indices: Vec<u8>,
+
+    /// Packed RGB triples of every pixel, only filled in with `PaletteMode::Rgb`.
+    rgb: Option<Vec<u8>>,
+
+    /// The LZW Minimum Code Size byte and image data sub-blocks as read from the stream, Block Terminator included.
+    /// Only kept with `DecodeOptions::keep_image_data`; `None` otherwise and for frames made in memory, e.g. by `Gif::crop`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    image_data: Option<Vec<u8>>,
+}
+
+/// Frames are equal when they decode to the same pixels, however their image data was compressed,
+/// so `Gif::frame_bytes` of two equal frames may differ.
+impl PartialEq for RasterData {
+    fn eq(&self, other: &Self) -> bool {
+        self.lzw_minimum_code_size == other.lzw_minimum_code_size
+            && self.indices == other.indices
+            && self.rgb == other.rgb
+    }
+}
+
+impl Eq for RasterData {}
+
 impl RasterData {
-    pub fn parse(_buf: &mut GifBuffer, image_descriptor: &ImageDescriptor) -> Self {
-        if image_descriptor.packed_fields.interlace_flag {}
-        RasterData {}
+    pub fn parse(
+        buf: &mut GifBuffer,
+        image_descriptor: &ImageDescriptor,
+        options: &DecodeOptions,
+    ) -> Result<Self, GifError> {
+        let offset = buf.get_pointer();
+        let lzw_minimum_code_size = buf.read_u8()?;
+        if !(2..=8).contains(&lzw_minimum_code_size) {
+            return Err(GifError::InvalidLzwCodeSize {
+                size: lzw_minimum_code_size,
+                offset,
+            });
+        }
+
+        let data = buf.read_data_sub_blocks()?;
+        let area = image_descriptor.area();
+        // a frame never needs more indices than it has pixels, so a short stream that expands
+        // to gigabytes stops there; only a `max_decoded_bytes` below the pixel count is an error
+        let max_decoded_bytes = options
+            .get_max_decoded_bytes()
+            .filter(|&max_decoded_bytes| max_decoded_bytes < area);
+        let max_len = max_decoded_bytes.unwrap_or(area);
+        // a well formed frame decodes to exactly one index per pixel,
+        // but a declared size alone must not be able to force a huge allocation
+        let capacity = if options.is_grow_indices() {
+            0
+        } else {
+            max_len.min(lzw::max_decoded_len(lzw_minimum_code_size, data.len()))
+        };
+        let mut indices: Vec<u8> = Vec::with_capacity(capacity);
+        match lzw::decode(lzw_minimum_code_size, &data, &mut indices, max_len) {
+            Err(GifError::DecodeLimitExceeded {
+                limit,
+                offset: index,
+            }) if max_decoded_bytes.is_some() => {
+                return Err(GifError::DecodeLimitExceeded {
+                    limit,
+                    offset: sub_block_offset(buf, offset + 1, index),
+                })
+            }
+            Err(GifError::InvalidLzwCode {
+                code,
+                offset: index,
+            }) if options.is_strict() => {
+                return Err(GifError::InvalidLzwCode {
+                    code,
+                    offset: sub_block_offset(buf, offset + 1, index),
+                })
+            }
+            // data past the last pixel is dropped like any other excess
+            Err(GifError::DecodeLimitExceeded { .. }) => {}
+            // outside strict mode keep the pixels decoded before the corruption
+            Err(err) if options.is_strict() => return Err(err),
+            _ => {}
+        }
+        // the code stream may end without an End of Information code, and then
+        // the padding bits of its last byte can read as one more code;
+        // the last string decoded may also run past the frame
+        indices.truncate(area);
+        if image_descriptor.packed_fields.interlace_flag {
+            indices = deinterlace(
+                &indices,
+                image_descriptor.image_width,
+                image_descriptor.image_height,
+            );
+        }
+
+        Ok(RasterData {
+            lzw_minimum_code_size,
+            indices,
+            rgb: None,
+            image_data: options
+                .is_keep_image_data()
+                .then(|| buf.as_slice()[offset..buf.get_pointer()].to_vec()),
+        })
+    }
+
+    pub fn indices(&self) -> &[u8] {
+        &self.indices
+    }
+
+    pub fn rgb(&self) -> Option<&[u8]> {
+        self.rgb.as_deref()
+    }
+
+    /// Decodes the image data like `parse`, additionally returning every LZW code read from the stream.
+    /// The indices are left in stream order, so interlaced rows are not rearranged.
+    /// A corrupt code ends decoding instead of failing; it is the last code of the trace.
+    #[cfg(feature = "debug-trace")]
+    pub fn decode_with_trace(buf: &mut GifBuffer) -> Result<(Vec<u8>, Vec<u16>), GifError> {
+        let offset = buf.get_pointer();
+        let lzw_minimum_code_size = buf.read_u8()?;
+        if !(2..=8).contains(&lzw_minimum_code_size) {
+            return Err(GifError::InvalidLzwCodeSize {
+                size: lzw_minimum_code_size,
+                offset,
+            });
+        }
+
+        let data = buf.read_data_sub_blocks()?;
+        let mut indices: Vec<u8> = Vec::new();
+        let mut codes: Vec<u16> = Vec::new();
+        let _ = lzw::decode_traced(
+            lzw_minimum_code_size,
+            &data,
+            &mut indices,
+            usize::MAX,
+            |code| codes.push(code),
+        );
+        Ok((indices, codes))
+    }
+
+    /// Steps over the image data by its sub-block sizes without decompressing it.
+    pub fn skip(buf: &mut GifBuffer) -> Result<(), GifError> {
+        let _lzw_minimum_code_size: u8 = buf.read_u8()?;
+        buf.skip_data_sub_blocks()
+    }
+
+    /// Compresses the indices again and writes the LZW Minimum Code Size and the image data sub-blocks.
+    /// Rows are put back into interlaced order when the image descriptor asks for it.
+    fn encode(&self, image_descriptor: &ImageDescriptor, out: &mut Vec<u8>) {
+        // every index has to fit in the literal codes of the table
+        let index_bits = self
+            .indices
+            .iter()
+            .max()
+            .map_or(0, |&max| 8 - max.leading_zeros());
+        let lzw_minimum_code_size = self.lzw_minimum_code_size.max(index_bits as u8);
+        let indices = if image_descriptor.packed_fields.interlace_flag {
+            interlace(
+                &self.indices,
+                image_descriptor.image_width,
+                image_descriptor.image_height,
+            )
+        } else {
+            self.indices.clone()
+        };
+        out.push(lzw_minimum_code_size);
+        encoder::write_data_sub_blocks(out, &lzw::encode(lzw_minimum_code_size, &indices));
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Position in the data stream of byte `index` of the concatenated payload of the data sub-blocks starting at `start`.
+fn sub_block_offset(buf: &GifBuffer, start: usize, index: usize) -> usize {
+    let mut position = start;
+    let mut remaining = index;
+    while let Ok(block_size) = buf.read_u8_at(position) {
+        if remaining < block_size as usize {
+            break;
+        }
+        remaining -= block_size as usize;
+        position += 1 + block_size as usize;
+    }
+    position + 1 + remaining
+}
+
+/// Recoverable problems of a single frame: reserved bits and a delay below `DecodeOptions::min_delay`.
+fn frame_warnings(
+    frame: usize,
+    group: &DescriptorGroup,
+    options: &DecodeOptions,
+) -> Vec<GifWarning> {
+    let mut warnings: Vec<GifWarning> = Vec::new();
+    let graphic_control_extension = group.graphic_control_extension.as_ref();
+    let gce_reserved = graphic_control_extension.map_or(0, GraphicControlExtension::reserved);
+    if group.image_descriptor.packed_fields.reserved != 0 || gce_reserved != 0 {
+        warnings.push(GifWarning::ReservedBitsSet { frame });
+    }
+    let delay = graphic_control_extension.map_or(0, GraphicControlExtension::delay_time);
+    if options
+        .get_min_delay()
+        .is_some_and(|min_delay| delay < min_delay)
+    {
+        warnings.push(GifWarning::DelayClamped { frame, delay });
+    }
+    warnings
+}
+
+/// The Graphic Control Extension of `previous` once the identical `next` is folded into it,
+/// or `None` if dropping `next` would change what is displayed.
+fn merged_control(
+    previous: &DescriptorGroup,
+    next: &DescriptorGroup,
+) -> Option<Option<GraphicControlExtension>> {
+    let delay_time = |group: &DescriptorGroup| {
+        group
+            .graphic_control_extension
+            .as_ref()
+            .map_or(0, GraphicControlExtension::delay_time)
+    };
+    let previous_disposal = previous.graphic_control_extension.as_ref().map_or(
+        DisposalMethod::None,
+        GraphicControlExtension::disposal_method,
+    );
+    if previous_disposal == DisposalMethod::RestoreBackground {
+        return None;
+    }
+    let delay_time = delay_time(previous).checked_add(delay_time(next))?;
+
+    let mut graphic_control_extension = match (
+        &previous.graphic_control_extension,
+        &next.graphic_control_extension,
+    ) {
+        (_, Some(next)) => next.clone(),
+        (Some(previous), None) => {
+            let mut previous = previous.clone();
+            previous.set_disposal_method(DisposalMethod::None);
+            previous
+        }
+        (None, None) => return Some(None),
+    };
+    graphic_control_extension.set_delay_time(delay_time);
+    Some(Some(graphic_control_extension))
+}
+
+/// Interlaced images store their rows in four passes:
+/// every 8th row starting at row 0, every 8th row starting at row 4,
+/// every 4th row starting at row 2 and every 2nd row starting at row 1.
+/// Each pass just stops at the last row, so heights that aren't a multiple of 8 (or shorter than a pass's
+/// starting row) still place every row exactly once.
+/// Missing rows of truncated data are 0, up to the last row that is present: like the indices of a truncated
+/// sequential image, the result only grows with the data, not with the declared size.
+fn deinterlace(indices: &[u8], width: u16, height: u16) -> Vec<u8> {
+    let width = width as usize;
+    let height = height as usize;
+    if width == 0 || height == 0 {
+        return indices.to_vec();
+    }
+
+    let mut rows: Vec<u8> = Vec::with_capacity(indices.len());
+    let mut interlaced_rows = indices.chunks(width);
+    for (start, step) in [(0, 8), (4, 8), (2, 4), (1, 2)] {
+        for row in (start..height).step_by(step) {
+            let Some(interlaced_row) = interlaced_rows.next() else {
+                return rows;
+            };
+            let offset = row * width;
+            let end = offset + interlaced_row.len();
+            if rows.len() < end {
+                rows.resize(end, 0);
+            }
+            rows[offset..end].copy_from_slice(interlaced_row);
+        }
+    }
+    rows
+}
+
+/// Puts sequential rows into the four pass order of an interlaced image, the inverse of `deinterlace`.
+fn interlace(indices: &[u8], width: u16, height: u16) -> Vec<u8> {
+    let width = width as usize;
+    let height = height as usize;
+    if width == 0 || height == 0 {
+        return indices.to_vec();
+    }
+
+    let mut interlaced_rows: Vec<u8> = Vec::with_capacity(indices.len());
+    for (start, step) in [(0, 8), (4, 8), (2, 4), (1, 2)] {
+        for row in (start..height).step_by(step) {
+            if let Some(sequential_row) = indices.get(row * width..(row + 1) * width) {
+                interlaced_rows.extend_from_slice(sequential_row);
+            }
+        }
+    }
+    interlaced_rows
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DescriptorGroup {
+    pub graphic_control_extension: Option<GraphicControlExtension>,
     pub image_descriptor: ImageDescriptor,
     pub local_color_map: Option<LocalColorMap>,
     pub raster_data: RasterData,
 }
 
+impl EncodeBlock for DescriptorGroup {
+    /// The frame as it appears in the Data Stream: Graphic Control Extension, Image Descriptor, Local Color Table and image data.
+    fn encode(&self, out: &mut Vec<u8>) {
+        if let Some(graphic_control_extension) = &self.graphic_control_extension {
+            graphic_control_extension.encode(out);
+        }
+        self.image_descriptor.encode(out);
+        if let Some(local_color_map) = &self.local_color_map {
+            local_color_map.encode(out);
+        }
+        self.raster_data.encode(&self.image_descriptor, out);
+    }
+}
+
 impl DescriptorGroup {
-    fn parse(buf: &mut GifBuffer) -> Self {
-        let image_descriptor: ImageDescriptor = ImageDescriptor::parse(buf);
-        let local_color_map: Option<LocalColorMap> = LocalColorMap::parse(buf, &image_descriptor);
-        let raster_data: RasterData = RasterData::parse(buf, &image_descriptor);
+    fn parse(buf: &mut GifBuffer, options: &DecodeOptions) -> Result<Self, GifError> {
+        let offset = buf.get_pointer();
+        let image_descriptor: ImageDescriptor = ImageDescriptor::parse(buf)?;
+        options.check_dimensions(
+            image_descriptor.image_width,
+            image_descriptor.image_height,
+            offset,
+        )?;
+        let local_color_map: Option<LocalColorMap> = LocalColorMap::parse(buf, &image_descriptor)?;
+        let raster_data: RasterData = RasterData::parse(buf, &image_descriptor, options)?;
 
-        DescriptorGroup {
+        Ok(DescriptorGroup {
+            graphic_control_extension: None,
             image_descriptor,
             local_color_map,
             raster_data,
+        })
+    }
+
+    /// Resolves every pixel through the active color table into packed RGB triples.
+    /// The local color table wins over the global one; indices outside the table render black.
+    /// With the `simd` feature the lookup runs on SIMD vectors, with byte-identical output.
+    pub fn render_rgb(&self, global_color_map: Option<&GlobalColorMap>) -> Vec<u8> {
+        let palette = self.active_palette(global_color_map);
+        #[cfg(feature = "simd")]
+        return crate::simd::expand_rgb(&self.raster_data.indices, palette);
+
+        #[cfg(not(feature = "simd"))]
+        crate::palette::expand_rgb(&self.raster_data.indices, palette)
+    }
+
+    /// Whether the Image Descriptor's Local Color Table Flag is set, i.e. the frame brings its own colors.
+    pub fn has_local_palette(&self) -> bool {
+        self.image_descriptor.packed_fields.local_color_table_flag
+    }
+
+    /// Placement of the frame on the Logical Screen as `(left, top, width, height)`, from its Image Descriptor.
+    pub fn bounding_box(&self) -> (u16, u16, u16, u16) {
+        self.image_descriptor.rect()
+    }
+
+    /// Hash of what the frame draws: its placement, Local Color Table, Transparent Color Index and pixels.
+    /// Two frames with the same hash look the same when drawn onto the same canvas.
+    /// The value is only meaningful within one process, so don't store it.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.bounding_box().hash(&mut hasher);
+        self.local_color_map
+            .as_ref()
+            .map(|local_color_map| local_color_map.palette.entries())
+            .hash(&mut hasher);
+        self.transparent_index().hash(&mut hasher);
+        self.raster_data.indices.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Whether both frames draw the same: what `content_hash` covers, compared field by field.
+    fn draws_like(&self, other: &DescriptorGroup) -> bool {
+        self.bounding_box() == other.bounding_box()
+            && self
+                .local_color_map
+                .as_ref()
+                .map(|map| map.palette.entries())
+                == other
+                    .local_color_map
+                    .as_ref()
+                    .map(|map| map.palette.entries())
+            && self.transparent_index() == other.transparent_index()
+            && self.raster_data.indices == other.raster_data.indices
+    }
+
+    /// The Transparent Color Index of the frame's Graphic Control Extension, when its Transparency Flag is set.
+    pub fn transparent_index(&self) -> Option<u8> {
+        self.graphic_control_extension
+            .as_ref()
+            .and_then(GraphicControlExtension::transparent_color_index)
+    }
+
+    /// Every pixel of the frame in row-major order as `(x, y, (r, g, b))`, with coordinates relative to the frame.
+    /// Colors resolve like in `render_rgb`, so transparency is not applied.
+    pub fn pixels<'a>(
+        &'a self,
+        global_color_map: Option<&'a GlobalColorMap>,
+    ) -> impl Iterator<Item = (u16, u16, (u8, u8, u8))> + 'a {
+        let palette = self.active_palette(global_color_map);
+        let image_width = self.image_descriptor.image_width as usize;
+        self.raster_data
+            .indices
+            .iter()
+            .enumerate()
+            .map(move |(position, &index)| {
+                let [r, g, b] = palette
+                    .and_then(|palette| palette.get(index))
+                    .copied()
+                    .unwrap_or([0, 0, 0]);
+                let x = (position % image_width) as u16;
+                let y = (position / image_width) as u16;
+                (x, y, (r, g, b))
+            })
+    }
+
+    /// Draws the frame into an RGB `canvas` of the logical screen at its `(image_left, image_top)` position.
+    /// Pixels falling outside the canvas are clipped and transparent pixels leave the canvas untouched.
+    pub fn render_onto(
+        &self,
+        canvas: &mut [u8],
+        canvas_width: u16,
+        canvas_height: u16,
+        global_color_map: Option<&GlobalColorMap>,
+    ) {
+        self.for_each_visible_pixel(
+            canvas_width,
+            canvas_height,
+            global_color_map,
+            |position, color| {
+                if let Some(pixel) = canvas.get_mut(position * 3..position * 3 + 3) {
+                    pixel.copy_from_slice(color);
+                }
+            },
+        );
+    }
+
+    /// Calls `draw` with the canvas position (`y * canvas_width + x`) and RGB color of every pixel of this frame
+    /// that lands on a `canvas_width` x `canvas_height` canvas. Transparent and clipped pixels are skipped.
+    pub(crate) fn for_each_visible_pixel(
+        &self,
+        canvas_width: u16,
+        canvas_height: u16,
+        global_color_map: Option<&GlobalColorMap>,
+        mut draw: impl FnMut(usize, &[u8]),
+    ) {
+        let Some(palette) = self.active_palette(global_color_map) else {
+            return;
+        };
+        let transparent_color_index = self.transparent_index();
+        let image_descriptor = &self.image_descriptor;
+        let image_width = image_descriptor.image_width as usize;
+        let image_height = image_descriptor.image_height as usize;
+        let canvas_width = canvas_width as usize;
+        let canvas_height = canvas_height as usize;
+
+        for (position, &index) in self.raster_data.indices.iter().enumerate() {
+            if Some(index) == transparent_color_index {
+                continue;
+            }
+            let (row, column) = (position / image_width, position % image_width);
+            let x = image_descriptor.image_left as usize + column;
+            let y = image_descriptor.image_top as usize + row;
+            if row >= image_height || x >= canvas_width || y >= canvas_height {
+                continue;
+            }
+
+            if let Some(color) = palette.get(index) {
+                draw(y * canvas_width + x, color);
+            }
         }
     }
+
+    /// The color table used by this frame: its local table, else the global one.
+    fn active_palette<'a>(
+        &'a self,
+        global_color_map: Option<&'a GlobalColorMap>,
+    ) -> Option<&'a Palette> {
+        match (&self.local_color_map, global_color_map) {
+            (Some(local_color_map), _) => Some(&local_color_map.palette),
+            (None, Some(global_color_map)) => Some(&global_color_map.palette),
+            (None, None) => None,
+        }
+    }
+
+    /// Steps over an image without decoding its raster data, returning its Image Descriptor.
+    fn skip(buf: &mut GifBuffer) -> Result<ImageDescriptor, GifError> {
+        let image_descriptor: ImageDescriptor = ImageDescriptor::parse(buf)?;
+        LocalColorMap::parse(buf, &image_descriptor)?;
+        RasterData::skip(buf)?;
+        Ok(image_descriptor)
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// What a pass over the blocks between the Global Color Table and the Trailer does with each of them.
+/// [`walk_blocks`] owns the dispatch on introducers and labels and hands every Graphic Control Extension
+/// to the graphic rendering block it applies to; extensions a visitor doesn't override are skipped by their sub-block sizes.
+trait BlockVisitor {
+    /// Called with `buf` on the Image Separator of image `frame`, which must be consumed whole.
+    /// `Break` ends the walk without reading the rest of the stream.
+    fn image(
+        &mut self,
+        buf: &mut GifBuffer,
+        frame: usize,
+        graphic_control_extension: Option<GraphicControlExtension>,
+    ) -> Result<ControlFlow<()>, GifError>;
+
+    /// Called with `buf` right after the label of a Plain Text Extension in front of image `before_frame`.
+    fn plain_text(
+        &mut self,
+        buf: &mut GifBuffer,
+        _before_frame: usize,
+        _graphic_control_extension: Option<GraphicControlExtension>,
+    ) -> Result<(), GifError> {
+        buf.skip_data_sub_blocks()
+    }
+
+    /// Called with `buf` right after `label` for every other extension but the Graphic Control Extension.
+    fn extension(
+        &mut self,
+        buf: &mut GifBuffer,
+        _label: u8,
+        _before_frame: usize,
+    ) -> Result<(), GifError> {
+        buf.skip_data_sub_blocks()
+    }
+
+    /// Called after every block but the Trailer.
+    fn block_done(&mut self, _buf: &GifBuffer) {}
+}
+
+/// Walks the blocks between the Global Color Table and the Trailer, handing each to `visitor`.
+/// Returns `None` instead of the Trailer when `visitor` breaks off at an image.
+fn walk_blocks(
+    buf: &mut GifBuffer,
+    visitor: &mut impl BlockVisitor,
+) -> Result<Option<Terminator>, GifError> {
+    // a graphic control extension applies to the next graphic rendering block only
+    let mut graphic_control_extension: Option<GraphicControlExtension> = None;
+    let mut frame_count: usize = 0;
+    // while the terminator bit (0x3B) or ';' is not found
+    // read the descriptors; running out of bytes first is an error
+    loop {
+        match buf.peek_u8()? {
+            EXTENSION_INTRODUCER => {
+                buf.skip_u8()?;
+                match buf.read_u8()? {
+                    GRAPHIC_CONTROL_LABEL => {
+                        graphic_control_extension = Some(GraphicControlExtension::parse(buf)?);
+                    }
+                    // the plain text is the graphic rendering block a preceding graphic control extension applies to
+                    PLAIN_TEXT_LABEL => {
+                        visitor.plain_text(buf, frame_count, graphic_control_extension.take())?
+                    }
+                    label => visitor.extension(buf, label, frame_count)?,
+                }
+            }
+            IMAGE_SEPARATOR => {
+                let flow = visitor.image(buf, frame_count, graphic_control_extension.take())?;
+                frame_count += 1;
+                if flow.is_break() {
+                    return Ok(None);
+                }
+            }
+            TRAILER => break,
+            label => {
+                return Err(GifError::UnknownBlock {
+                    label,
+                    offset: buf.get_pointer(),
+                })
+            }
+        }
+        visitor.block_done(buf);
+    }
+    Ok(Some(Terminator::parse(buf)?))
+}
+
+/// The full decode of `Gif::parse_blocks`: every block is parsed and collected, images are handed to `on_frame`.
+struct DecodeVisitor<'a, F> {
+    options: &'a DecodeOptions,
+    global_color_map: Option<&'a GlobalColorMap>,
+    application_extensions: &'a mut Vec<ApplicationExtension>,
+    comment_extensions: &'a mut Vec<CommentExtension>,
+    plain_text_extensions: &'a mut Vec<PlainTextExtension>,
+    on_frame: F,
+}
+
+impl<F: FnMut(DescriptorGroup) -> ControlFlow<()>> BlockVisitor for DecodeVisitor<'_, F> {
+    fn image(
+        &mut self,
+        buf: &mut GifBuffer,
+        frame: usize,
+        graphic_control_extension: Option<GraphicControlExtension>,
+    ) -> Result<ControlFlow<()>, GifError> {
+        let options = self.options;
+        if options.is_cancelled() {
+            return Err(GifError::Cancelled);
+        }
+        if let Some(max_frames) = options
+            .get_max_frames()
+            .filter(|&max_frames| frame >= max_frames)
+        {
+            return Err(GifError::LimitExceeded {
+                limit: "max_frames",
+                value: frame + 1,
+                max: max_frames,
+                offset: buf.get_pointer(),
+            });
+        }
+        let mut descriptor_group = DescriptorGroup::parse(buf, options)?;
+        descriptor_group.graphic_control_extension = graphic_control_extension;
+        if options.get_palette_mode() == PaletteMode::Rgb {
+            descriptor_group.raster_data.rgb =
+                Some(descriptor_group.render_rgb(self.global_color_map));
+        }
+        Ok((self.on_frame)(descriptor_group))
+    }
+
+    fn plain_text(
+        &mut self,
+        buf: &mut GifBuffer,
+        before_frame: usize,
+        graphic_control_extension: Option<GraphicControlExtension>,
+    ) -> Result<(), GifError> {
+        let mut plain_text_extension = PlainTextExtension::parse(buf)?;
+        plain_text_extension.graphic_control_extension = graphic_control_extension;
+        plain_text_extension.before_frame = before_frame;
+        self.plain_text_extensions.push(plain_text_extension);
+        Ok(())
+    }
+
+    fn extension(
+        &mut self,
+        buf: &mut GifBuffer,
+        label: u8,
+        before_frame: usize,
+    ) -> Result<(), GifError> {
+        match label {
+            APPLICATION_EXTENSION_LABEL => {
+                let mut application_extension = ApplicationExtension::parse(buf)?;
+                application_extension.before_frame = before_frame;
+                self.application_extensions.push(application_extension);
+            }
+            COMMENT_LABEL => {
+                let mut comment_extension = CommentExtension::parse(buf)?;
+                comment_extension.before_frame = before_frame;
+                self.comment_extensions.push(comment_extension);
+            }
+            _ => buf.skip_data_sub_blocks()?,
+        }
+        Ok(())
+    }
+
+    fn block_done(&mut self, buf: &GifBuffer) {
+        self.options
+            .report_progress(buf.get_pointer(), buf.get_size());
+    }
+}
+
+/// `Gif::decode_frame`: decodes image `index` and steps over every image before it.
+struct FrameVisitor {
+    index: usize,
+    frame_count: usize,
+    descriptor_group: Option<DescriptorGroup>,
+}
+
+impl BlockVisitor for FrameVisitor {
+    fn image(
+        &mut self,
+        buf: &mut GifBuffer,
+        frame: usize,
+        graphic_control_extension: Option<GraphicControlExtension>,
+    ) -> Result<ControlFlow<()>, GifError> {
+        if frame != self.index {
+            DescriptorGroup::skip(buf)?;
+            self.frame_count += 1;
+            return Ok(ControlFlow::Continue(()));
+        }
+        let mut descriptor_group = DescriptorGroup::parse(buf, &DecodeOptions::default())?;
+        descriptor_group.graphic_control_extension = graphic_control_extension;
+        self.descriptor_group = Some(descriptor_group);
+        Ok(ControlFlow::Break(()))
+    }
+}
+
+/// `Gif::probe`: counts the images and their pixels without decoding any.
+#[derive(Default)]
+struct ProbeVisitor {
+    frame_count: usize,
+    decoded_size: usize,
+}
+
+impl BlockVisitor for ProbeVisitor {
+    fn image(
+        &mut self,
+        buf: &mut GifBuffer,
+        _frame: usize,
+        _graphic_control_extension: Option<GraphicControlExtension>,
+    ) -> Result<ControlFlow<()>, GifError> {
+        let image_descriptor = DescriptorGroup::skip(buf)?;
+        self.decoded_size = self.decoded_size.saturating_add(image_descriptor.area());
+        self.frame_count += 1;
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+/// This block is a single-field block indicating the end of the GIF Data Stream. It contains the fixed value 0x3B.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Terminator {}
-impl Terminator {
-    pub fn parse(_buf: &mut GifBuffer) -> Self {
-        Terminator {}
+impl GifBlock for Terminator {
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError> {
+        expect_byte(buf, TRAILER)?;
+        Ok(Terminator {})
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl EncodeBlock for Terminator {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(TRAILER);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Gif {
     pub signature: GifSignature,
     pub logical_screen_descriptor: LogicalScreenDescriptor,
     pub global_color_map: Option<GlobalColorMap>,
+    pub application_extensions: Vec<ApplicationExtension>,
+    pub comment_extensions: Vec<CommentExtension>,
+    /// Text drawn by the stream, each with the Graphic Control Extension in front of it.
+    pub plain_text_extensions: Vec<PlainTextExtension>,
     pub descriptor_groups: Vec<DescriptorGroup>,
-    // pub terminator: Terminator,
+    /// `None` when the stream ended before the Trailer, see `DecodeOptions::lenient_truncation`.
+    pub terminator: Option<Terminator>,
+    /// Recoverable problems found while decoding, in stream order.
+    pub warnings: Vec<GifWarning>,
 }
 
 impl Gif {
-    pub fn decode(file_path: &str) -> Self {
-        let mut buf = GifBuffer::read(file_path);
-        let signature = GifSignature::parse(&mut buf);
-        println!("INFO: Magic value: {signature:#?}");
+    pub fn decode(file_path: &str) -> Result<Self, GifError> {
+        Self::decode_with_options(file_path, &DecodeOptions::default())
+    }
 
-        // assert_eq!( GifVersion::GIF87a, signature.version, "ERROR: Program only works with GIF87a version");
+    /// Like [`Gif::decode`], for paths that need not be valid UTF-8, e.g. ones listed from a directory.
+    pub fn decode_path(file_path: &Path) -> Result<Self, GifError> {
+        Self::decode_buffer(GifBuffer::read_path(file_path)?, &DecodeOptions::default())
+    }
 
-        let logical_screen_descriptor = LogicalScreenDescriptor::parse(&mut buf);
-        println!("INFO: Screen Descriptor: {logical_screen_descriptor:#?}");
+    pub fn decode_with_options(file_path: &str, options: &DecodeOptions) -> Result<Self, GifError> {
+        Self::decode_buffer(GifBuffer::read(file_path)?, options)
+    }
+
+    /// Decodes a GIF that is already in memory.
+    /// Malformed input of any kind is reported as an error rather than a panic; `fuzz/` holds a cargo-fuzz target for this.
+    ///
+    /// ```
+    /// use gif_parser::gif::Gif;
+    ///
+    /// // a single white pixel
+    /// let data = [
+    ///     b'G', b'I', b'F', b'8', b'9', b'a', // Header
+    ///     0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, // Logical Screen Descriptor
+    ///     0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, // Global Color Table
+    ///     0x2C, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, // Image Descriptor
+    ///     0x02, 0x02, 0x4C, 0x01, 0x00, // Image Data
+    ///     0x3B, // Trailer
+    /// ];
+    /// let gif = Gif::decode_bytes(&data).unwrap();
+    /// assert_eq!(gif.descriptor_groups.len(), 1);
+    /// assert_eq!(gif.first_frame_rgb().unwrap(), (1, 1, vec![0xFF, 0xFF, 0xFF]));
+    /// ```
+    pub fn decode_bytes(data: &[u8]) -> Result<Self, GifError> {
+        Self::decode_buffer(GifBuffer::from_bytes(data), &DecodeOptions::default())
+    }
 
-        let global_color_map = GlobalColorMap::parse(&mut buf, &logical_screen_descriptor);
-        if let Some(gcm) = &global_color_map {
-            println!("INFO: {gcm}");
+    /// Decodes a GIF embedded in a larger container, starting at `offset` of `data`.
+    /// Error offsets stay relative to the start of `data`; an `offset` past its end is an `UnexpectedEof`.
+    pub fn decode_bytes_at(data: &[u8], offset: usize) -> Result<Self, GifError> {
+        if offset > data.len() {
+            return Err(GifError::UnexpectedEof { offset: data.len() });
         }
+        let mut buf = GifBuffer::from_bytes(data);
+        buf.skip(offset)?;
+        Self::decode_buffer(buf, &DecodeOptions::default())
+    }
 
+    fn decode_buffer(mut buf: GifBuffer, options: &DecodeOptions) -> Result<Self, GifError> {
+        let (signature, logical_screen_descriptor, global_color_map) =
+            Self::parse_header(&mut buf, options)?;
+
+        let mut application_extensions: Vec<ApplicationExtension> = Vec::new();
+        let mut comment_extensions: Vec<CommentExtension> = Vec::new();
+        let mut plain_text_extensions: Vec<PlainTextExtension> = Vec::new();
         let mut descriptor_groups: Vec<DescriptorGroup> = Vec::new();
-        // while the terminator bit (0x3B) or ';' is not found
-        // read the descriptors
-        // while buf.peek_u8() !=  0x3B {
-        let descriptor_group: DescriptorGroup = DescriptorGroup::parse(&mut buf);
-        descriptor_groups.push(descriptor_group);
-        // }
-        println!("INFO: Image Descriptors: {descriptor_groups:#?}");
-        Gif {
+        let mut warnings: Vec<GifWarning> = Vec::new();
+        let terminator = match Self::parse_blocks(
+            &mut buf,
+            options,
+            global_color_map.as_ref(),
+            &mut application_extensions,
+            &mut comment_extensions,
+            &mut plain_text_extensions,
+            |descriptor_group| {
+                let frame = descriptor_groups.len();
+                warnings.extend(frame_warnings(frame, &descriptor_group, options));
+                descriptor_groups.push(descriptor_group);
+                ControlFlow::Continue(())
+            },
+        ) {
+            Ok(terminator) => terminator,
+            // a partial download still yields every frame that arrived completely
+            Err(GifError::UnexpectedEof { .. } | GifError::TruncatedColorTable { .. })
+                if options.is_lenient_truncation() =>
+            {
+                None
+            }
+            Err(err) => return Err(err),
+        };
+
+        if terminator.is_none() {
+            warnings.push(GifWarning::Truncated {
+                frame_count: descriptor_groups.len(),
+            });
+        }
+        let trailing_bytes = buf.get_size() - buf.get_pointer();
+        if trailing_bytes > 0 && terminator.is_some() {
+            warnings.push(GifWarning::TrailingBytes {
+                count: trailing_bytes,
+            });
+        }
+
+        Ok(Gif {
             signature,
             logical_screen_descriptor,
             global_color_map,
+            application_extensions,
+            comment_extensions,
+            plain_text_extensions,
+            descriptor_groups,
+            terminator,
+            warnings,
+        })
+    }
+
+    /// Reads the Header, Logical Screen Descriptor and Global Color Table, applying the options that concern them.
+    fn parse_header(
+        buf: &mut GifBuffer,
+        options: &DecodeOptions,
+    ) -> Result<
+        (
+            GifSignature,
+            LogicalScreenDescriptor,
+            Option<GlobalColorMap>,
+        ),
+        GifError,
+    > {
+        if let Some(max_offset) = options.get_scan_for_magic() {
+            // without a match the signature check below reports the bytes at offset 0
+            let data = buf.as_slice();
+            let window = &data[..data.len().min(max_offset.saturating_add(GIF_MAGIC.len()))];
+            if let Some(offset) = window
+                .windows(GIF_MAGIC.len())
+                .position(|bytes| bytes.eq_ignore_ascii_case(GIF_MAGIC))
+            {
+                buf.skip(offset)?;
+            }
+        }
+        let signature = GifSignature::parse(buf)?;
+        if let Some(version) = options.get_version() {
+            if signature.version != version {
+                return Err(GifError::UnsupportedVersion(signature.version));
+            }
+        }
+
+        // assert_eq!( GifVersion::GIF87a, signature.version, "ERROR: Program only works with GIF87a version");
+
+        let logical_screen_descriptor = LogicalScreenDescriptor::parse(buf)?;
+        options.check_dimensions(
+            logical_screen_descriptor.logical_screen_width,
+            logical_screen_descriptor.logical_screen_height,
+            logical_screen_descriptor.offset,
+        )?;
+        let global_color_map = GlobalColorMap::parse(buf, &logical_screen_descriptor)?;
+
+        Ok((signature, logical_screen_descriptor, global_color_map))
+    }
+
+    /// Decodes the file frame by frame, handing each to `callback` as soon as it is parsed
+    /// instead of collecting them, so memory use doesn't grow with the number of frames.
+    /// Returning `ControlFlow::Break` from `callback` stops decoding without reading the rest of the stream.
+    pub fn decode_streaming<F>(file_path: &str, callback: F) -> Result<(), GifError>
+    where
+        F: FnMut(&DescriptorGroup) -> ControlFlow<()>,
+    {
+        Self::decode_streaming_with_options(file_path, &DecodeOptions::default(), callback)
+    }
+
+    /// Like [`Gif::decode_streaming`], applying `options` the way [`Gif::decode_with_options`] does:
+    /// limits, strictness, cancellation and progress all hold for the streamed frames.
+    /// With `lenient_truncation` a stream cut off midway ends after its last complete frame.
+    pub fn decode_streaming_with_options<F>(
+        file_path: &str,
+        options: &DecodeOptions,
+        mut callback: F,
+    ) -> Result<(), GifError>
+    where
+        F: FnMut(&DescriptorGroup) -> ControlFlow<()>,
+    {
+        let mut buf = GifBuffer::read(file_path)?;
+        let (_signature, _logical_screen_descriptor, global_color_map) =
+            Self::parse_header(&mut buf, options)?;
+
+        match Self::parse_blocks(
+            &mut buf,
+            options,
+            global_color_map.as_ref(),
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &mut Vec::new(),
+            |descriptor_group| callback(&descriptor_group),
+        ) {
+            Err(GifError::UnexpectedEof { .. } | GifError::TruncatedColorTable { .. })
+                if options.is_lenient_truncation() =>
+            {
+                Ok(())
+            }
+            result => result.map(drop),
+        }
+    }
+
+    /// Parses the blocks between the Global Color Table and the Trailer, passing every frame to `on_frame` as it completes.
+    /// Returns `None` instead of the Trailer when `on_frame` breaks off early.
+    fn parse_blocks(
+        buf: &mut GifBuffer,
+        options: &DecodeOptions,
+        global_color_map: Option<&GlobalColorMap>,
+        application_extensions: &mut Vec<ApplicationExtension>,
+        comment_extensions: &mut Vec<CommentExtension>,
+        plain_text_extensions: &mut Vec<PlainTextExtension>,
+        on_frame: impl FnMut(DescriptorGroup) -> ControlFlow<()>,
+    ) -> Result<Option<Terminator>, GifError> {
+        let mut visitor = DecodeVisitor {
+            options,
+            global_color_map,
+            application_extensions,
+            comment_extensions,
+            plain_text_extensions,
+            on_frame,
+        };
+        let terminator = walk_blocks(buf, &mut visitor)?;
+        if terminator.is_some() {
+            options.report_progress(buf.get_pointer(), buf.get_size());
+        }
+        Ok(terminator)
+    }
+
+    /// Decodes every `.gif` file in `dir`, in path order, keeping the result of each file
+    /// so one corrupt file doesn't stop the batch. A directory that can't be listed is reported as one failed entry.
+    pub fn decode_all_in_dir(dir: &str) -> Vec<(PathBuf, Result<Self, GifError>)> {
+        Self::for_each_gif_in_dir(dir, Self::decode_path)
+    }
+
+    /// Like [`Gif::decode_all_in_dir`], but only [`Gif::probe`]s each file, for building a catalog of a directory
+    /// without running the LZW decoder on any image.
+    pub fn index_dir(dir: &str) -> Vec<(PathBuf, Result<GifProbe, GifError>)> {
+        Self::for_each_gif_in_dir(dir, Self::probe_path)
+    }
+
+    fn for_each_gif_in_dir<T>(
+        dir: &str,
+        f: impl Fn(&Path) -> Result<T, GifError>,
+    ) -> Vec<(PathBuf, Result<T, GifError>)> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) => return vec![(PathBuf::from(dir), Err(err.into()))],
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"))
+            })
+            .collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let result = f(&path);
+                (path, result)
+            })
+            .collect()
+    }
+
+    /// Decodes only the frame at `index`; every image before it is skipped
+    /// by its sub-block sizes without running the LZW decoder.
+    pub fn decode_frame(file_path: &str, index: usize) -> Result<DescriptorGroup, GifError> {
+        let mut buf = GifBuffer::read(file_path)?;
+        let _signature = GifSignature::parse(&mut buf)?;
+        let logical_screen_descriptor = LogicalScreenDescriptor::parse(&mut buf)?;
+        let _global_color_map = GlobalColorMap::parse(&mut buf, &logical_screen_descriptor)?;
+
+        let mut visitor = FrameVisitor {
+            index,
+            frame_count: 0,
+            descriptor_group: None,
+        };
+        walk_blocks(&mut buf, &mut visitor)?;
+        visitor.descriptor_group.ok_or(GifError::FrameNotFound {
+            index,
+            frame_count: visitor.frame_count,
+        })
+    }
+
+    /// Reads the header and counts the images, stepping over extensions and image data
+    /// by their sub-block sizes without running the LZW decoder.
+    pub fn probe(file_path: &str) -> Result<GifProbe, GifError> {
+        Self::probe_path(Path::new(file_path))
+    }
+
+    /// Like [`Gif::probe`], for paths that need not be valid UTF-8.
+    pub fn probe_path(file_path: &Path) -> Result<GifProbe, GifError> {
+        let mut buf = GifBuffer::read_path(file_path)?;
+        let signature = GifSignature::parse(&mut buf)?;
+        let logical_screen_descriptor = LogicalScreenDescriptor::parse(&mut buf)?;
+        let global_color_map = GlobalColorMap::parse(&mut buf, &logical_screen_descriptor)?;
+
+        let mut visitor = ProbeVisitor::default();
+        walk_blocks(&mut buf, &mut visitor)?;
+
+        Ok(GifProbe {
+            version: signature.version,
+            width: logical_screen_descriptor.logical_screen_width,
+            height: logical_screen_descriptor.logical_screen_height,
+            global_color_table_present: global_color_map.is_some(),
+            frame_count: visitor.frame_count,
+            decoded_size: visitor.decoded_size,
+        })
+    }
+
+    pub fn version(&self) -> &GifVersion {
+        &self.signature.version
+    }
+
+    /// Only GIF89a defines the Graphic Control Extension that carries frame delays and disposal,
+    /// so a GIF87a stream can hold several images but cannot describe an animation.
+    pub fn supports_animation(&self) -> bool {
+        self.signature.version == GifVersion::GIF89a
+    }
+
+    /// Loop count from the NETSCAPE2.0 Application Extension; 0 means loop forever.
+    pub fn loop_count(&self) -> Option<u16> {
+        self.application_extensions
+            .iter()
+            .find_map(ApplicationExtension::netscape_loop_count)
+    }
+
+    /// XMP metadata from the `XMP Data` Application Extension.
+    pub fn xmp(&self) -> Option<String> {
+        self.application_extensions
+            .iter()
+            .find_map(ApplicationExtension::xmp)
+            .map(String::from)
+    }
+
+    /// ICC color profile from the `ICCRGBG1012` Application Extension.
+    pub fn icc_profile(&self) -> Option<Vec<u8>> {
+        self.application_extensions
+            .iter()
+            .find_map(ApplicationExtension::icc_profile)
+            .map(<[u8]>::to_vec)
+    }
+
+    /// Encodes the GIF back into a Data Stream: every frame in order, with the Application, Comment and Plain Text Extensions
+    /// between the same images they were decoded between.
+    pub fn encode(&self) -> Vec<u8> {
+        self.encode_to(Vec::new())
+            .expect("writing to a Vec never fails")
+    }
+
+    /// Like `encode`, but writes the Data Stream to `writer` and hands it back once the Trailer is written.
+    pub fn encode_to<W: Write>(&self, writer: W) -> Result<W, GifError> {
+        self.encode_with_extensions(
+            writer,
+            self.application_extensions.iter(),
+            &self.comment_extensions,
+            &self.plain_text_extensions,
+        )
+    }
+
+    /// Re-encodes the GIF without metadata, keeping only the frames and their timing.
+    /// Comment and Plain Text Extensions are dropped, as is every Application Extension except the looping extension,
+    /// since removing that one would change how often the animation plays.
+    pub fn strip_metadata(&self) -> Vec<u8> {
+        self.encode_with_extensions(
+            Vec::new(),
+            self.application_extensions
+                .iter()
+                .filter(|application_extension| {
+                    application_extension.netscape_loop_count().is_some()
+                }),
+            &[],
+            &[],
+        )
+        .expect("writing to a Vec never fails")
+    }
+
+    fn encode_with_extensions<'a, W: Write>(
+        &self,
+        writer: W,
+        application_extensions: impl Iterator<Item = &'a ApplicationExtension>,
+        comment_extensions: &[CommentExtension],
+        plain_text_extensions: &[PlainTextExtension],
+    ) -> Result<W, GifError> {
+        let mut encoder = GifEncoder::new(writer);
+        encoder.write_header(
+            &self.signature,
+            &self.logical_screen_descriptor,
+            self.global_color_map.as_ref(),
+        )?;
+        // every extension goes back into the gap between images it was decoded from
+        let frame_count = self.descriptor_groups.len();
+        let mut application_extensions = application_extensions.peekable();
+        let mut comment_extensions = comment_extensions.iter().peekable();
+        let mut plain_text_extensions = plain_text_extensions.iter().peekable();
+        for frame in 0..=frame_count {
+            let in_gap = |before_frame: usize| before_frame.min(frame_count) <= frame;
+            while let Some(application_extension) =
+                application_extensions.next_if(|extension| in_gap(extension.before_frame()))
+            {
+                encoder.write_application_extension(application_extension)?;
+            }
+            while let Some(comment_extension) =
+                comment_extensions.next_if(|extension| in_gap(extension.before_frame()))
+            {
+                encoder.write_comment_extension(comment_extension)?;
+            }
+            while let Some(plain_text_extension) =
+                plain_text_extensions.next_if(|extension| in_gap(extension.before_frame()))
+            {
+                encoder.write_plain_text_extension(plain_text_extension)?;
+            }
+            if let Some(descriptor_group) = self.descriptor_groups.get(frame) {
+                encoder.write_frame(descriptor_group)?;
+            }
+        }
+        encoder.finish()
+    }
+
+    /// Crops every frame to `(left, top, width, height)` of the Logical Screen and encodes the result,
+    /// with the crop's top left corner as the new origin. Frames reaching past the crop are clipped to it;
+    /// a frame entirely outside keeps its delay as a single transparent pixel, since an image can't be empty.
+    /// Plain Text Extensions are dropped, their text grids being placed on the uncropped screen.
+    pub fn crop(
+        &self,
+        (left, top, width, height): (u16, u16, u16, u16),
+    ) -> Result<Vec<u8>, GifError> {
+        let screen_descriptor = &self.logical_screen_descriptor;
+        let right = left as u32 + width as u32;
+        let bottom = top as u32 + height as u32;
+        if width == 0
+            || height == 0
+            || right > screen_descriptor.logical_screen_width as u32
+            || bottom > screen_descriptor.logical_screen_height as u32
+        {
+            return Err(GifError::CropOutOfBounds {
+                left,
+                top,
+                width,
+                height,
+            });
+        }
+
+        let descriptor_groups = self
+            .descriptor_groups
+            .iter()
+            .map(|group| {
+                let (frame_left, frame_top, frame_width, frame_height) = group.bounding_box();
+                let frame_right = frame_left as u32 + frame_width as u32;
+                let frame_bottom = frame_top as u32 + frame_height as u32;
+                let (x0, x1) = ((frame_left as u32).max(left as u32), frame_right.min(right));
+                let (y0, y1) = ((frame_top as u32).max(top as u32), frame_bottom.min(bottom));
+                let raster_data = &group.raster_data;
+
+                if x0 >= x1 || y0 >= y1 {
+                    let transparent_color_index = group.transparent_index().unwrap_or(0);
+                    return DescriptorGroup {
+                        graphic_control_extension: Some(
+                            GraphicControlExtension::transparent_placeholder(
+                                group.graphic_control_extension.as_ref(),
+                                transparent_color_index,
+                            ),
+                        ),
+                        image_descriptor: group.image_descriptor.with_rect((0, 0, 1, 1)),
+                        local_color_map: group.local_color_map.clone(),
+                        raster_data: RasterData {
+                            lzw_minimum_code_size: raster_data.lzw_minimum_code_size,
+                            indices: vec![transparent_color_index],
+                            rgb: None,
+                            image_data: None,
+                        },
+                    };
+                }
+
+                let indices = (y0..y1)
+                    .flat_map(|y| (x0..x1).map(move |x| (y, x)))
+                    .map(|(y, x)| {
+                        let offset = (y - frame_top as u32) as usize * frame_width as usize
+                            + (x - frame_left as u32) as usize;
+                        raster_data.indices.get(offset).copied().unwrap_or(0)
+                    })
+                    .collect();
+                DescriptorGroup {
+                    graphic_control_extension: group.graphic_control_extension.clone(),
+                    image_descriptor: group.image_descriptor.with_rect((
+                        (x0 - left as u32) as u16,
+                        (y0 - top as u32) as u16,
+                        (x1 - x0) as u16,
+                        (y1 - y0) as u16,
+                    )),
+                    local_color_map: group.local_color_map.clone(),
+                    raster_data: RasterData {
+                        lzw_minimum_code_size: raster_data.lzw_minimum_code_size,
+                        indices,
+                        rgb: None,
+                        image_data: None,
+                    },
+                }
+            })
+            .collect();
+
+        let cropped = Gif {
+            signature: self.signature.clone(),
+            logical_screen_descriptor: LogicalScreenDescriptor {
+                logical_screen_width: width,
+                logical_screen_height: height,
+                ..screen_descriptor.clone()
+            },
+            global_color_map: self.global_color_map.clone(),
+            application_extensions: self.application_extensions.clone(),
+            comment_extensions: self.comment_extensions.clone(),
+            // the text grids are placed on the old screen
+            plain_text_extensions: Vec::new(),
+            descriptor_groups,
+            terminator: Some(Terminator {}),
+            warnings: Vec::new(),
+        };
+        Ok(cropped.encode())
+    }
+
+    /// Merges runs of identical consecutive frames, found by [`DescriptorGroup::content_hash`], into their first frame,
+    /// which is then shown for the sum of their delays and disposed of like the last one.
+    /// Drawing an identical frame again changes nothing unless the one before restores to background,
+    /// so such frames are kept, as are frames with an extension in front of them and merges whose delay would not fit
+    /// the 16 bit Delay Time.
+    pub fn optimize(&self) -> Gif {
+        self.optimize_with(DescriptorGroup::content_hash)
+    }
+
+    /// `optimize` with the frame hash as a parameter, so tests can force collisions.
+    fn optimize_with(&self, content_hash: impl Fn(&DescriptorGroup) -> u64) -> Gif {
+        let mut descriptor_groups: Vec<DescriptorGroup> =
+            Vec::with_capacity(self.descriptor_groups.len());
+        // where each original frame ends up, for moving the extensions between frames along
+        let mut new_frames: Vec<usize> = Vec::with_capacity(self.descriptor_groups.len() + 1);
+        let mut previous_hash = None;
+        for (frame, group) in self.descriptor_groups.iter().enumerate() {
+            let hash = content_hash(group);
+            // equal hashes only make a merge likely, a collision must not drop a frame;
+            // an extension in front of the frame would lose its place between the two
+            if let Some(previous) = descriptor_groups.last_mut().filter(|previous| {
+                previous_hash == Some(hash)
+                    && previous.draws_like(group)
+                    && !self.has_extension_before(frame)
+            }) {
+                if let Some(graphic_control_extension) = merged_control(previous, group) {
+                    previous.graphic_control_extension = graphic_control_extension;
+                    new_frames.push(descriptor_groups.len() - 1);
+                    continue;
+                }
+            }
+            previous_hash = Some(hash);
+            new_frames.push(descriptor_groups.len());
+            descriptor_groups.push(group.clone());
+        }
+        new_frames.push(descriptor_groups.len());
+
+        let mut optimized = Gif {
             descriptor_groups,
+            ..self.clone()
+        };
+        let new_frame = |before_frame: usize| new_frames[before_frame.min(new_frames.len() - 1)];
+        for extension in &mut optimized.application_extensions {
+            extension.before_frame = new_frame(extension.before_frame);
+        }
+        for extension in &mut optimized.comment_extensions {
+            extension.before_frame = new_frame(extension.before_frame);
+        }
+        for extension in &mut optimized.plain_text_extensions {
+            extension.before_frame = new_frame(extension.before_frame);
+        }
+        optimized
+    }
+
+    /// Whether an Application, Comment or Plain Text Extension sits right in front of image `frame`.
+    fn has_extension_before(&self, frame: usize) -> bool {
+        self.application_extensions
+            .iter()
+            .map(ApplicationExtension::before_frame)
+            .chain(
+                self.comment_extensions
+                    .iter()
+                    .map(CommentExtension::before_frame),
+            )
+            .chain(
+                self.plain_text_extensions
+                    .iter()
+                    .map(PlainTextExtension::before_frame),
+            )
+            .any(|before_frame| before_frame == frame)
+    }
+
+    /// Replaces every color index `i` by `mapping[i]` in all frames, including the Transparent Color Indices.
+    ///
+    /// Without `permute_palette` the color tables stay as they are, so pixels change color: a palette swap.
+    /// With it, the color at `i` moves to `mapping[i]` in every table, so the image looks the same with reordered indices;
+    /// `mapping` should then be a permutation of the table indices.
+    pub fn recolor(&mut self, mapping: &[u8; 256], permute_palette: bool) {
+        if permute_palette {
+            if let Some(global_color_map) = &mut self.global_color_map {
+                global_color_map.palette.permute(mapping);
+            }
+        }
+        for group in &mut self.descriptor_groups {
+            for index in &mut group.raster_data.indices {
+                *index = mapping[*index as usize];
+            }
+            // the stream bytes decode to the old indices, `frame_bytes` compresses the new ones instead
+            group.raster_data.image_data = None;
+            if let Some(graphic_control_extension) = &mut group.graphic_control_extension {
+                if let Some(transparent_index) = graphic_control_extension.transparent_color_index()
+                {
+                    graphic_control_extension
+                        .set_transparent_color_index(mapping[transparent_index as usize]);
+                }
+            }
+            if permute_palette {
+                if let Some(local_color_map) = &mut group.local_color_map {
+                    local_color_map.palette.permute(mapping);
+                }
+            }
+            // keep the colors resolved with `PaletteMode::Rgb` in step with the new indices
+            if group.raster_data.rgb.is_some() {
+                group.raster_data.rgb = Some(group.render_rgb(self.global_color_map.as_ref()));
+            }
+        }
+    }
+
+    pub fn animation(&self) -> Animation {
+        Animation::new(self)
+    }
+
+    /// Index of the frame to display `ms` milliseconds into playback, see [`Animation::frame_at`].
+    pub fn frame_at_time(&self, ms: u64) -> Option<usize> {
+        self.animation().frame_at(Duration::from_millis(ms))
+    }
+
+    /// The image data of frame `index` as it appears in the stream: the LZW Minimum Code Size byte followed by
+    /// the data sub-blocks and the Block Terminator, so it can be copied into another stream without recompressing.
+    /// The stream bytes are only kept with `DecodeOptions::keep_image_data`. Without them, and for frames made in memory
+    /// or changed since, e.g. by `Gif::recolor`, the indices are compressed instead.
+    pub fn frame_bytes(&self, index: usize) -> Result<Vec<u8>, GifError> {
+        let group = self
+            .descriptor_groups
+            .get(index)
+            .ok_or(GifError::FrameNotFound {
+                index,
+                frame_count: self.descriptor_groups.len(),
+            })?;
+        match &group.raster_data.image_data {
+            Some(image_data) => Ok(image_data.clone()),
+            None => {
+                let mut out = Vec::new();
+                group.raster_data.encode(&group.image_descriptor, &mut out);
+                Ok(out)
+            }
+        }
+    }
+
+    /// Every frame as it is displayed, as RGBA canvases of the logical screen size.
+    /// See [`Compositor`] for how frames are layered.
+    pub fn rgba_frames(&self) -> Result<Vec<Vec<u8>>, GifError> {
+        Ok(Compositor::new(self)?.collect())
+    }
+
+    /// Frame `index` as displayed: the RGBA canvas after every frame up to and including `index`
+    /// has been disposed of and composited, the same image as `rgba_frames()[index]`.
+    pub fn frame_rgba(&self, index: usize) -> Result<Vec<u8>, GifError> {
+        Compositor::new(self)?
+            .nth(index)
+            .ok_or(GifError::FrameNotFound {
+                index,
+                frame_count: self.descriptor_groups.len(),
+            })
+    }
+
+    /// The RGBA canvas as it looks after the last frame, with every frame composited as in [`Gif::rgba_frames`].
+    /// Without frames this is a fully transparent canvas of the logical screen size.
+    pub fn canvas_rgba(&self) -> Result<Vec<u8>, GifError> {
+        match Compositor::new(self)?.last() {
+            Some(pixels) => Ok(pixels),
+            None => Ok(Canvas::for_screen(&self.logical_screen_descriptor)?.into_pixels()),
+        }
+    }
+
+    /// The first frame as a still image: `(width, height, rgb)` of the logical screen with frame 0 drawn at its offset.
+    /// Pixels the frame doesn't cover, or leaves transparent, are black.
+    pub fn first_frame_rgb(&self) -> Result<(u16, u16, Vec<u8>), GifError> {
+        let group = self
+            .descriptor_groups
+            .first()
+            .ok_or(GifError::FrameNotFound {
+                index: 0,
+                frame_count: 0,
+            })?;
+        let width = self.logical_screen_descriptor.logical_screen_width;
+        let height = self.logical_screen_descriptor.logical_screen_height;
+        let offset = Some(self.logical_screen_descriptor.offset);
+        let mut rgb: Vec<u8> = render::pixel_buffer(width, height, 3, offset)?;
+        group.render_onto(&mut rgb, width, height, self.global_color_map.as_ref());
+        Ok((width, height, rgb))
+    }
+
+    /// Renders the first frame as `width` columns of ASCII art, one line per row, for a quick look in a terminal.
+    /// Each character averages the luminance of the pixels it covers; rows cover twice the height of columns
+    /// since terminal cells are about twice as tall as they are wide.
+    pub fn to_ascii_art(&self, width: usize) -> String {
+        const RAMP: &[u8] = b" .:-=+*#%@";
+        let Ok((screen_width, screen_height, rgb)) = self.first_frame_rgb() else {
+            return String::new();
+        };
+        let (screen_width, screen_height) = (screen_width as usize, screen_height as usize);
+        if width == 0 || screen_width == 0 || screen_height == 0 {
+            return String::new();
         }
+        let height = (screen_height * width / (screen_width * 2)).max(1);
+
+        let mut art = String::with_capacity((width + 1) * height);
+        for row in 0..height {
+            let top = row * screen_height / height;
+            let bottom = ((row + 1) * screen_height / height).max(top + 1);
+            for column in 0..width {
+                let left = column * screen_width / width;
+                let right = ((column + 1) * screen_width / width).max(left + 1);
+                let mut luminance = 0;
+                for y in top..bottom {
+                    for x in left..right {
+                        let pixel = &rgb[(y * screen_width + x) * 3..][..3];
+                        luminance += (299 * pixel[0] as usize
+                            + 587 * pixel[1] as usize
+                            + 114 * pixel[2] as usize)
+                            / 1000;
+                    }
+                }
+                luminance /= (bottom - top) * (right - left);
+                art.push(RAMP[luminance * (RAMP.len() - 1) / 255] as char);
+            }
+            art.push('\n');
+        }
+        art
+    }
+
+    /// The most frequent color of the first frame, e.g. for a placeholder background while the GIF loads.
+    /// Transparent pixels and indices outside the active color table are not counted; ties go to the lower index.
+    pub fn dominant_color(&self) -> Option<(u8, u8, u8)> {
+        let group = self.descriptor_groups.first()?;
+        let palette = group.active_palette(self.global_color_map.as_ref())?;
+        let transparent_color_index = group.transparent_index();
+
+        let mut counts = [0usize; 256];
+        for &index in group.raster_data.indices() {
+            counts[index as usize] += 1;
+        }
+        let (_, [r, g, b]) = (0..=u8::MAX)
+            .filter(|&index| Some(index) != transparent_color_index && counts[index as usize] > 0)
+            .filter_map(|index| Some((counts[index as usize], *palette.get(index)?)))
+            .rev()
+            .max_by_key(|&(count, _)| count)?;
+        Some((r, g, b))
+    }
+
+    /// Runs every structural check over the decoded stream and reports all violations at once:
+    /// frame sizes and bounds, reserved bits, color table coverage of the background and every pixel, and the Trailer.
+    pub fn validate(&self) -> Result<(), Vec<GifError>> {
+        let mut errors: Vec<GifError> = Vec::new();
+        let screen_descriptor = &self.logical_screen_descriptor;
+
+        if let Some(global_color_map) = &self.global_color_map {
+            if screen_descriptor.background_color_index as usize >= global_color_map.palette.len() {
+                errors.push(GifError::BackgroundColorOutOfRange(
+                    screen_descriptor.background_color_index,
+                ));
+            }
+        }
+
+        for (frame, group) in self.descriptor_groups.iter().enumerate() {
+            let image_descriptor = &group.image_descriptor;
+            if image_descriptor.image_width == 0 || image_descriptor.image_height == 0 {
+                errors.push(GifError::ZeroSizedFrame {
+                    offset: image_descriptor.offset,
+                });
+            }
+            let right = image_descriptor.image_left as u32 + image_descriptor.image_width as u32;
+            let bottom = image_descriptor.image_top as u32 + image_descriptor.image_height as u32;
+            if right > screen_descriptor.logical_screen_width as u32
+                || bottom > screen_descriptor.logical_screen_height as u32
+            {
+                errors.push(GifError::FrameOutOfBounds { frame });
+            }
+
+            let gce_reserved = group
+                .graphic_control_extension
+                .as_ref()
+                .map_or(0, GraphicControlExtension::reserved);
+            if image_descriptor.packed_fields.reserved != 0 || gce_reserved != 0 {
+                errors.push(GifError::ReservedBitsSet { frame });
+            }
+
+            match group.active_palette(self.global_color_map.as_ref()) {
+                None => errors.push(GifError::MissingColorTable { frame }),
+                Some(palette) => {
+                    if let Some(&index) = group.raster_data.indices.iter().max() {
+                        if index as usize >= palette.len() {
+                            errors.push(GifError::ColorIndexOutOfRange { frame, index });
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.terminator.is_none() {
+            errors.push(GifError::MissingTrailer);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Bytes of color indices the frames decode to: the sum of `width * height` over all Image Descriptors.
+    /// It only reads the descriptors, so `Gif::probe` reports the same number before any LZW data is decompressed.
+    pub fn estimate_decoded_size(&self) -> usize {
+        self.descriptor_groups
+            .iter()
+            .map(|group| group.image_descriptor.area())
+            .fold(0, usize::saturating_add)
+    }
+
+    /// Estimated heap usage in bytes: the color tables, the decoded indices, the image data kept
+    /// with `DecodeOptions::keep_image_data` and, in `PaletteMode::Rgb`, the resolved colors of every frame.
+    pub fn memory_footprint(&self) -> usize {
+        let global_color_table = self
+            .global_color_map
+            .as_ref()
+            .map_or(0, |global_color_map| global_color_map.palette.len() * 3);
+        let frames: usize = self
+            .descriptor_groups
+            .iter()
+            .map(|group| {
+                let local_color_table = group
+                    .local_color_map
+                    .as_ref()
+                    .map_or(0, |local_color_map| local_color_map.palette.len() * 3);
+                let raster_data = &group.raster_data;
+                local_color_table
+                    + raster_data.indices.len()
+                    + raster_data.rgb.as_ref().map_or(0, Vec::len)
+                    + raster_data.image_data.as_ref().map_or(0, Vec::len)
+            })
+            .sum();
+        global_color_table + frames
+    }
+
+    /// One call overview of the color tables used by this GIF.
+    pub fn palette_report(&self) -> PaletteReport {
+        let packed_fields = &self.logical_screen_descriptor.packed_fields;
+        let local_color_table_count = self
+            .descriptor_groups
+            .iter()
+            .filter(|group| group.local_color_map.is_some())
+            .count();
+        let any_frame_sorted = self
+            .descriptor_groups
+            .iter()
+            .any(|group| group.image_descriptor.packed_fields.sort_flag);
+
+        PaletteReport {
+            global_color_table_present: self.global_color_map.is_some(),
+            global_color_table_size: 1 << packed_fields.global_color_table_size,
+            global_color_table_sorted: packed_fields.sort_flag,
+            local_color_table_count,
+            any_frame_sorted,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for Gif {
+    type Error = GifError;
+
+    /// Same as [`Gif::decode_bytes`].
+    ///
+    /// ```
+    /// use gif_parser::gif::Gif;
+    ///
+    /// // a single black pixel on a GIF87a screen
+    /// let data = [
+    ///     b'G', b'I', b'F', b'8', b'7', b'a', // Header
+    ///     0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, // Logical Screen Descriptor
+    ///     0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, // Global Color Table
+    ///     0x2C, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, // Image Descriptor
+    ///     0x02, 0x02, 0x44, 0x01, 0x00, // Image Data
+    ///     0x3B, // Trailer
+    /// ];
+    /// let gif = Gif::try_from(&data[..])?;
+    /// assert!(!gif.supports_animation());
+    /// assert_eq!(gif.first_frame_rgb()?, (1, 1, vec![0x00, 0x00, 0x00]));
+    /// # Ok::<(), gif_parser::error::GifError>(())
+    /// ```
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Gif::decode_bytes(data)
+    }
+}
+
+/// Summary of the color table structure of a GIF, see [`Gif::palette_report`].
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PaletteReport {
+    /// Global Color Table Flag of the Logical Screen Descriptor.
+    pub global_color_table_present: bool,
+
+    /// Number of colors declared for the Global Color Table.
+    /// The field is set by encoders even when no Global Color Table follows.
+    pub global_color_table_size: usize,
+
+    /// Sort Flag of the Global Color Table.
+    pub global_color_table_sorted: bool,
+
+    /// Number of frames that carry their own Local Color Table.
+    pub local_color_table_count: usize,
+
+    /// Whether any frame sets the Sort Flag of its Image Descriptor.
+    pub any_frame_sorted: bool,
+}
+
+/// Header level facts of a GIF gathered without decoding its images, see [`Gif::probe`].
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GifProbe {
+    pub version: GifVersion,
+
+    /// Logical Screen Width.
+    pub width: u16,
+
+    /// Logical Screen Height.
+    pub height: u16,
+
+    /// Global Color Table Flag of the Logical Screen Descriptor.
+    pub global_color_table_present: bool,
+
+    /// Number of images in the stream.
+    pub frame_count: usize,
+
+    /// Bytes of color indices all images decode to, see `Gif::estimate_decoded_size`.
+    pub decoded_size: usize,
+}
+
+// Every parsed type owns plain data, so decoded GIFs can be moved to and
+// shared between threads (e.g. decoding many files from a rayon iterator).
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Gif>();
+    assert_send_sync::<GifBuffer>();
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2x1 GIF of `frames` with the given indices, each shown for 10 hundredths.
+    fn gif_of(frames: &[[u8; 2]]) -> Gif {
+        let mut data = b"GIF89a\x02\x00\x01\x00\x81\x00\x00".to_vec();
+        data.extend([0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 255, 0]);
+        for indices in frames {
+            data.extend([0x21, 0xF9, 4, 0x04, 10, 0, 0, 0]);
+            data.extend([0x2C, 0, 0, 0, 0, 2, 0, 1, 0, 0]);
+            // Clear Code, both indices, End of Information, three bits each
+            let codes = 4u32 | (indices[0] as u32) << 3 | (indices[1] as u32) << 6 | 5 << 9;
+            data.extend([2, 2, codes as u8, (codes >> 8) as u8, 0]);
+        }
+        data.push(TRAILER);
+        Gif::decode_bytes(&data).unwrap()
+    }
+
+    /// Frames whose hashes collide are still compared and only merged when they draw the same.
+    #[test]
+    fn optimize_confirms_hash_matches() {
+        let gif = gif_of(&[[0, 1], [1, 0], [1, 0]]);
+        let optimized = gif.optimize_with(|_| 0);
+        assert_eq!(optimized.descriptor_groups.len(), 2);
+        assert_eq!(optimized.descriptor_groups[0].raster_data.indices, [0, 1]);
+        assert_eq!(optimized.descriptor_groups[1].raster_data.indices, [1, 0]);
+        assert_eq!(
+            optimized.descriptor_groups[1]
+                .graphic_control_extension
+                .as_ref()
+                .map(GraphicControlExtension::delay_time),
+            Some(20)
+        );
+        assert_eq!(optimized, gif.optimize());
+    }
+
+    /// A frame emptied after decoding is reported at the Image Descriptor it was read from,
+    /// which the decoder itself would have rejected.
+    #[test]
+    fn validate_reports_zero_sized_frames() {
+        let mut gif = gif_of(&[[0, 1], [1, 0]]);
+        assert!(gif.validate().is_ok());
+        gif.descriptor_groups[1].image_descriptor.image_width = 0;
+        let errors = gif.validate().unwrap_err();
+        // Header, Logical Screen Descriptor, Global Color Table, the first frame and the second Graphic Control Extension
+        assert!(
+            matches!(errors[..], [GifError::ZeroSizedFrame { offset: 56 }]),
+            "{errors:?}"
+        );
+        assert_eq!(
+            gif.descriptor_groups[1].image_descriptor.offset,
+            13 + 12 + 23 + 8
+        );
     }
 }
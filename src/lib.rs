@@ -1,2 +1,15 @@
+pub mod animation;
+#[cfg(feature = "apng")]
+mod apng;
+pub mod encoder;
+pub mod error;
+pub mod extension;
 pub mod gif;
+mod lzw;
+pub mod options;
+pub mod palette;
 pub mod parser;
+pub mod render;
+#[cfg(feature = "simd")]
+mod simd;
+pub mod warning;
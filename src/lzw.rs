@@ -0,0 +1,250 @@
+use crate::error::GifError;
+use std::collections::HashMap;
+
+/// Codes in a GIF LZW stream never grow beyond 12 bits.
+const MAX_CODE_SIZE: u8 = 12;
+const MAX_CODES: usize = 1 << MAX_CODE_SIZE;
+
+/// Upper bound on the number of indices `data_len` bytes of code stream can decode to:
+/// every code takes at least `minimum_code_size + 1` bits and no string is longer than the code table.
+pub(crate) fn max_decoded_len(minimum_code_size: u8, data_len: usize) -> usize {
+    (data_len.saturating_mul(8) / (minimum_code_size as usize + 1)).saturating_mul(MAX_CODES)
+}
+
+/// Reads variable width codes from the concatenated image data sub-blocks.
+/// Codes are packed least significant bit first.
+struct BitReader<'a> {
+    data: &'a [u8],
+    bit_position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        BitReader {
+            data,
+            bit_position: 0,
+        }
+    }
+
+    fn read(&mut self, code_size: u8) -> Option<u16> {
+        let code_size = code_size as usize;
+        if self.bit_position + code_size > self.data.len() * 8 {
+            return None;
+        }
+        let mut code = 0u16;
+        for bit in 0..code_size {
+            let position = self.bit_position + bit;
+            let value = (self.data[position / 8] >> (position % 8)) & 0b1;
+            code |= (value as u16) << bit;
+        }
+        self.bit_position += code_size;
+        Some(code)
+    }
+
+    /// Index into `data` of the byte the code of `code_size` bits just read started in.
+    fn code_offset(&self, code_size: u8) -> usize {
+        (self.bit_position - code_size as usize) / 8
+    }
+}
+
+/// Decompresses a GIF LZW code stream into color table indices, appending them to `indices`.
+/// On error `indices` keeps everything decoded before the bad code,
+/// whose `InvalidLzwCode` offset is an index into `data` rather than into the data stream.
+/// Some encoders omit the End of Information code, so running out of data ends the stream as well.
+/// Decoding stops with `DecodeLimitExceeded` once `indices` grows past `max_len`, its offset an index into `data` too.
+///
+/// The table is kept as `prefix`/`suffix` chains, so every entry is a
+/// previous entry plus one trailing byte.
+pub(crate) fn decode(
+    minimum_code_size: u8,
+    data: &[u8],
+    indices: &mut Vec<u8>,
+    max_len: usize,
+) -> Result<(), GifError> {
+    decode_traced(minimum_code_size, data, indices, max_len, |_| {})
+}
+
+/// Same as `decode`, but hands every code read from the stream to `trace`,
+/// including clear and end of information codes, before it is interpreted.
+pub(crate) fn decode_traced(
+    minimum_code_size: u8,
+    data: &[u8],
+    indices: &mut Vec<u8>,
+    max_len: usize,
+    mut trace: impl FnMut(u16),
+) -> Result<(), GifError> {
+    let clear_code: u16 = 1 << minimum_code_size;
+    let end_code: u16 = clear_code + 1;
+
+    let mut prefix = vec![0u16; MAX_CODES];
+    let mut suffix = vec![0u8; MAX_CODES];
+    let mut length = vec![0u16; MAX_CODES];
+    for code in 0..clear_code as usize {
+        suffix[code] = code as u8;
+        length[code] = 1;
+    }
+
+    let mut code_size = minimum_code_size + 1;
+    let mut next_code = end_code + 1;
+    let mut previous: Option<u16> = None;
+    let mut reader = BitReader::new(data);
+
+    while let Some(code) = reader.read(code_size) {
+        trace(code);
+        if code == clear_code {
+            code_size = minimum_code_size + 1;
+            next_code = end_code + 1;
+            previous = None;
+            continue;
+        }
+        if code == end_code {
+            break;
+        }
+
+        let Some(previous_code) = previous else {
+            // the first code after a clear must be a literal
+            if code >= clear_code {
+                return Err(GifError::InvalidLzwCode {
+                    code,
+                    offset: reader.code_offset(code_size),
+                });
+            }
+            indices.push(code as u8);
+            if indices.len() > max_len {
+                return Err(GifError::DecodeLimitExceeded {
+                    limit: max_len,
+                    offset: reader.code_offset(code_size),
+                });
+            }
+            previous = Some(code);
+            continue;
+        };
+
+        let start = indices.len();
+        if code < next_code {
+            emit(indices, code, &prefix, &suffix, &length);
+        } else if code == next_code {
+            // the code being defined right now: previous string + its own first byte
+            emit(indices, previous_code, &prefix, &suffix, &length);
+            indices.push(indices[start]);
+        } else {
+            return Err(GifError::InvalidLzwCode {
+                code,
+                offset: reader.code_offset(code_size),
+            });
+        }
+        if indices.len() > max_len {
+            return Err(GifError::DecodeLimitExceeded {
+                limit: max_len,
+                offset: reader.code_offset(code_size),
+            });
+        }
+
+        if (next_code as usize) < MAX_CODES {
+            let next = next_code as usize;
+            prefix[next] = previous_code;
+            suffix[next] = indices[start];
+            length[next] = length[previous_code as usize] + 1;
+            next_code += 1;
+            if next_code == 1 << code_size && code_size < MAX_CODE_SIZE {
+                code_size += 1;
+            }
+        }
+        previous = Some(code);
+    }
+
+    Ok(())
+}
+
+/// Appends the string for `code` by walking its prefix chain backwards.
+fn emit(indices: &mut Vec<u8>, code: u16, prefix: &[u16], suffix: &[u8], length: &[u16]) {
+    let start = indices.len();
+    indices.resize(start + length[code as usize] as usize, 0);
+    let mut code = code as usize;
+    for position in (start..indices.len()).rev() {
+        indices[position] = suffix[code];
+        code = prefix[code] as usize;
+    }
+}
+
+/// Packs variable width codes into bytes, least significant bit first.
+struct BitWriter {
+    data: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter {
+            data: Vec::new(),
+            buffer: 0,
+            bits: 0,
+        }
+    }
+
+    fn write(&mut self, code: u16, code_size: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += code_size;
+        while self.bits >= 8 {
+            self.data.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    /// Pads the last partial byte with zero bits.
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.data.push(self.buffer as u8);
+        }
+        self.data
+    }
+}
+
+/// Compresses color table indices into a GIF LZW code stream, the inverse of `decode`.
+/// Every index must be below `1 << minimum_code_size`.
+///
+/// The stream starts with a clear code and ends with the End of Information code.
+/// When the table is full a clear code is written and it starts over.
+pub(crate) fn encode(minimum_code_size: u8, indices: &[u8]) -> Vec<u8> {
+    let clear_code: u16 = 1 << minimum_code_size;
+    let end_code: u16 = clear_code + 1;
+
+    // (prefix code, next index) -> code of the extended string
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut code_size = minimum_code_size + 1;
+    let mut next_code = end_code + 1;
+    let mut writer = BitWriter::new();
+    writer.write(clear_code, code_size);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        writer.write(end_code, code_size);
+        return writer.finish();
+    };
+    let mut current = first as u16;
+    for &index in rest {
+        if let Some(&code) = table.get(&(current, index)) {
+            current = code;
+            continue;
+        }
+        writer.write(current, code_size);
+        if (next_code as usize) < MAX_CODES {
+            table.insert((current, index), next_code);
+            next_code += 1;
+            // the decoder defines each code one step later, so it widens on the code after this one
+            if next_code > 1 << code_size && code_size < MAX_CODE_SIZE {
+                code_size += 1;
+            }
+        } else {
+            writer.write(clear_code, code_size);
+            table.clear();
+            code_size = minimum_code_size + 1;
+            next_code = end_code + 1;
+        }
+        current = index as u16;
+    }
+    writer.write(current, code_size);
+    writer.write(end_code, code_size);
+    writer.finish()
+}
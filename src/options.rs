@@ -0,0 +1,287 @@
+use crate::error::GifError;
+use crate::gif::GifVersion;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// How the colors of decoded frames are made available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaletteMode {
+    /// Frames only keep their color table indices; resolve them with `DescriptorGroup::render_rgb`.
+    #[default]
+    Indexed,
+    /// Frames are additionally resolved to RGB while decoding, see `RasterData::rgb`.
+    Rgb,
+}
+
+/// Receives `(bytes_consumed, total_bytes)` while decoding, see `DecodeOptions::progress`.
+/// Two callbacks are only equal when they are the same closure.
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(usize, usize) + Send + Sync>);
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+impl PartialEq for ProgressCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ProgressCallback {}
+
+/// Flag checked between frames, see `DecodeOptions::cancel_flag`.
+/// Two flags are only equal when they are the same `AtomicBool`.
+#[derive(Debug, Clone)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl PartialEq for CancelFlag {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancelFlag {}
+
+/// Settings for `Gif::decode_with_options`, built fluently:
+/// `DecodeOptions::new().strict(false).palette_mode(PaletteMode::Rgb)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// Fail on recoverable corruption instead of keeping what could be decoded,
+    /// e.g. an invalid LZW code ends the frame early when not strict.
+    strict: bool,
+
+    palette_mode: PaletteMode,
+
+    /// Only accept data streams of this version.
+    version: Option<GifVersion>,
+
+    /// Return the frames decoded so far when the stream ends before the Trailer instead of failing.
+    /// A frame cut off midway is dropped and `Gif::terminator` is `None`.
+    lenient_truncation: bool,
+
+    /// Upper bound on the decompressed size of a single frame, one byte per pixel index.
+    /// A frame never decodes past its own pixel count, this bounds it further; `None` leaves only that bound.
+    /// The limit applies to each frame on its own, so bound the whole stream together with `max_frames`.
+    max_decoded_bytes: Option<usize>,
+
+    /// Most frames a stream may contain; decoding stops with `LimitExceeded` at the next one.
+    max_frames: Option<usize>,
+
+    /// Widest Logical Screen or image a stream may declare, checked before any pixels are decoded.
+    max_width: Option<u16>,
+
+    /// Tallest Logical Screen or image a stream may declare, checked before any pixels are decoded.
+    max_height: Option<u16>,
+
+    /// Smallest frame delay in hundredths of a second used by `Animation::with_options`;
+    /// shorter delays, including 0, are raised to it like browsers do.
+    min_delay: Option<u16>,
+
+    /// Look for the `GIF8` magic within this many bytes of the start instead of requiring it at offset 0,
+    /// for mis-served files with stray leading bytes such as a BOM.
+    scan_for_magic: Option<usize>,
+
+    /// Called after every block of the Data Stream, so large animations can drive a progress bar.
+    progress: Option<ProgressCallback>,
+
+    /// Checked in front of every frame; once set, decoding stops with `GifError::Cancelled`.
+    cancel_flag: Option<CancelFlag>,
+
+    /// Keep each frame's image data as read from the stream, so `Gif::frame_bytes` returns it without recompressing.
+    /// Off by default: it holds a copy of the compressed stream next to the decoded frames.
+    keep_image_data: bool,
+
+    /// Start each frame's index buffer empty instead of reserving one index per pixel,
+    /// only so `benches/decode.rs` can time the decoder both ways.
+    grow_indices: bool,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        DecodeOptions {
+            strict: true,
+            palette_mode: PaletteMode::default(),
+            version: None,
+            lenient_truncation: false,
+            max_decoded_bytes: None,
+            max_frames: None,
+            max_width: None,
+            max_height: None,
+            min_delay: None,
+            scan_for_magic: None,
+            progress: None,
+            cancel_flag: None,
+            keep_image_data: false,
+            grow_indices: false,
+        }
+    }
+}
+
+impl DecodeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn palette_mode(mut self, palette_mode: PaletteMode) -> Self {
+        self.palette_mode = palette_mode;
+        self
+    }
+
+    pub fn version(mut self, version: GifVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn lenient_truncation(mut self, lenient_truncation: bool) -> Self {
+        self.lenient_truncation = lenient_truncation;
+        self
+    }
+
+    pub fn max_decoded_bytes(mut self, max_decoded_bytes: usize) -> Self {
+        self.max_decoded_bytes = Some(max_decoded_bytes);
+        self
+    }
+
+    pub fn max_frames(mut self, max_frames: usize) -> Self {
+        self.max_frames = Some(max_frames);
+        self
+    }
+
+    pub fn max_width(mut self, max_width: u16) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn max_height(mut self, max_height: u16) -> Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    pub fn min_delay(mut self, min_delay: u16) -> Self {
+        self.min_delay = Some(min_delay);
+        self
+    }
+
+    pub fn scan_for_magic(mut self, max_offset: usize) -> Self {
+        self.scan_for_magic = Some(max_offset);
+        self
+    }
+
+    /// Reports how far decoding got as `(bytes_consumed, total_bytes)` of the input.
+    /// The last call is made once the Trailer is read, with both values equal unless bytes trail it.
+    pub fn progress(mut self, callback: impl Fn(usize, usize) + Send + Sync + 'static) -> Self {
+        self.progress = Some(ProgressCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Lets another thread abort a long decode by setting `flag`, e.g. when the user closes the view.
+    /// The flag is read between frames, so a frame being decoded is finished first.
+    pub fn cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(CancelFlag(flag));
+        self
+    }
+
+    pub fn keep_image_data(mut self, keep_image_data: bool) -> Self {
+        self.keep_image_data = keep_image_data;
+        self
+    }
+
+    #[doc(hidden)]
+    pub fn grow_indices(mut self, grow_indices: bool) -> Self {
+        self.grow_indices = grow_indices;
+        self
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    pub fn get_palette_mode(&self) -> PaletteMode {
+        self.palette_mode
+    }
+
+    pub fn get_version(&self) -> Option<GifVersion> {
+        self.version
+    }
+
+    pub fn is_lenient_truncation(&self) -> bool {
+        self.lenient_truncation
+    }
+
+    pub fn get_max_decoded_bytes(&self) -> Option<usize> {
+        self.max_decoded_bytes
+    }
+
+    pub fn get_max_frames(&self) -> Option<usize> {
+        self.max_frames
+    }
+
+    pub fn get_max_width(&self) -> Option<u16> {
+        self.max_width
+    }
+
+    pub fn get_max_height(&self) -> Option<u16> {
+        self.max_height
+    }
+
+    /// Fails with `LimitExceeded` when a `width` x `height` screen or image declared at `offset`
+    /// is past `max_width` or `max_height`.
+    pub(crate) fn check_dimensions(
+        &self,
+        width: u16,
+        height: u16,
+        offset: usize,
+    ) -> Result<(), GifError> {
+        for (limit, value, max) in [
+            ("max_width", width, self.max_width),
+            ("max_height", height, self.max_height),
+        ] {
+            if let Some(max) = max.filter(|&max| value > max) {
+                return Err(GifError::LimitExceeded {
+                    limit,
+                    value: value as usize,
+                    max: max as usize,
+                    offset,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_min_delay(&self) -> Option<u16> {
+        self.min_delay
+    }
+
+    pub fn get_scan_for_magic(&self) -> Option<usize> {
+        self.scan_for_magic
+    }
+
+    pub fn is_keep_image_data(&self) -> bool {
+        self.keep_image_data
+    }
+
+    pub(crate) fn is_grow_indices(&self) -> bool {
+        self.grow_indices
+    }
+
+    pub(crate) fn report_progress(&self, bytes_consumed: usize, total_bytes: usize) {
+        if let Some(ProgressCallback(callback)) = &self.progress {
+            callback(bytes_consumed, total_bytes);
+        }
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel_flag
+            .as_ref()
+            .is_some_and(|CancelFlag(flag)| flag.load(Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,107 @@
+use crate::encoder::EncodeBlock;
+use crate::error::GifError;
+use crate::parser::GifBuffer;
+
+/// Most colors a color table can hold: the 3 bit size field tops out at `2^(7+1)`, and indices are one byte.
+pub const MAX_COLORS: usize = 256;
+
+/// The red-green-blue entries of a color table, in color index order.
+/// Both the Global and the Local Color Table store their colors this way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Palette {
+    entries: Vec<[u8; 3]>,
+}
+
+impl Palette {
+    /// Reads a table of `len` colors, 3 bytes each.
+    /// The table stores 8 bit intensities as-is, independent of the table size.
+    /// More than [`MAX_COLORS`] colors is an `InvalidColorTableSize` error, checked before anything is read.
+    pub fn parse(buf: &mut GifBuffer, len: usize) -> Result<Self, GifError> {
+        if len > MAX_COLORS {
+            return Err(GifError::InvalidColorTableSize {
+                len,
+                offset: buf.get_pointer(),
+            });
+        }
+        let size = len * 3;
+        let available = buf.get_size() - buf.get_pointer();
+        if size > available {
+            return Err(GifError::TruncatedColorTable {
+                declared: size,
+                available,
+                offset: buf.get_pointer(),
+            });
+        }
+
+        let entries = buf
+            .read_slice_ref(size)?
+            .chunks_exact(3)
+            .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+            .collect();
+        Ok(Palette { entries })
+    }
+
+    /// The color of `index`, `None` past the end of the table.
+    pub fn get(&self, index: u8) -> Option<&[u8; 3]> {
+        self.entries.get(index as usize)
+    }
+
+    /// Number of colors in the table.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> &[[u8; 3]] {
+        &self.entries
+    }
+
+    /// Moves the color at every index `i` to `mapping[i]`; indices mapped past the end of the table are dropped.
+    pub(crate) fn permute(&mut self, mapping: &[u8; 256]) {
+        let mut entries = self.entries.clone();
+        for (index, color) in self.entries.iter().enumerate() {
+            if let Some(entry) = entries.get_mut(mapping[index] as usize) {
+                *entry = *color;
+            }
+        }
+        self.entries = entries;
+    }
+
+    /// Index of the table color closest to `(r, g, b)` by Euclidean distance in RGB; ties go to the lowest index.
+    pub fn nearest_index(&self, (r, g, b): (u8, u8, u8)) -> u8 {
+        let distance = |color: &[u8; 3]| -> u32 {
+            [(color[0], r), (color[1], g), (color[2], b)]
+                .iter()
+                .map(|&(channel, target)| (channel as i32 - target as i32).pow(2) as u32)
+                .sum()
+        };
+        self.entries
+            .iter()
+            .enumerate()
+            .min_by_key(|&(_, color)| distance(color))
+            .map_or(0, |(index, _)| index as u8)
+    }
+}
+
+/// Resolves color table indices into packed RGB triples one pixel at a time; indices outside the table are black.
+/// This is the portable path of `DescriptorGroup::render_rgb`, which the `simd` feature has to match byte for byte.
+pub(crate) fn expand_rgb(indices: &[u8], palette: Option<&Palette>) -> Vec<u8> {
+    let mut rgb: Vec<u8> = Vec::with_capacity(indices.len() * 3);
+    for &index in indices {
+        match palette.and_then(|palette| palette.get(index)) {
+            Some(color) => rgb.extend_from_slice(color),
+            None => rgb.extend_from_slice(&[0, 0, 0]),
+        }
+    }
+    rgb
+}
+
+impl EncodeBlock for Palette {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.entries.as_flattened());
+    }
+}
@@ -1,6 +1,7 @@
+use crate::error::GifError;
 use std::fs::File;
 use std::io::{BufReader, Read};
-use std::os::windows::fs::MetadataExt;
+use std::path::Path;
 
 #[derive(Debug, PartialEq)]
 pub struct GifBuffer {
@@ -10,34 +11,35 @@ pub struct GifBuffer {
 }
 
 impl GifBuffer {
-    pub fn read(file_path: &str) -> Self {
-        println!("INFO: Loading file {file_path}...");
-        let mut file = File::open(&file_path)
-            .map_err(|err| {
-                eprintln!("ERROR: Unable to open file: {file_path}, due to error: {err}");
-            })
-            .unwrap();
+    pub fn read(file_path: &str) -> Result<Self, GifError> {
+        Self::read_path(Path::new(file_path))
+    }
+
+    /// Like [`GifBuffer::read`], for paths that need not be valid UTF-8.
+    pub fn read_path(file_path: &Path) -> Result<Self, GifError> {
+        let mut file = File::open(file_path)?;
 
-        println!("INFO: Reading Metadata...");
-        let metadata = file.metadata().expect("ERROR: Unable to parse metadata");
-        let file_size: usize = metadata.file_size() as usize;
+        let metadata = file.metadata()?;
+        let file_size: usize = metadata.len() as usize;
 
-        println!("INFO: Reading data into buffer...");
         let mut b_reader: BufReader<&mut File> = BufReader::new(&mut file);
         let mut buf: Vec<u8> = vec![0u8; file_size];
 
-        b_reader
-            .read(&mut buf)
-            .map_err(|err| {
-                eprintln!("ERROR: Unable to read binary data into buffer: {err}");
-            })
-            .unwrap();
-        println!("INFO: {size} bytes read into buffer.", size = buf.len());
+        b_reader.read_exact(&mut buf)?;
 
-        GifBuffer {
+        Ok(GifBuffer {
             buffer: buf.into_boxed_slice(),
             size: file_size,
             pointer: 0,
+        })
+    }
+
+    /// Wraps a copy of `data`, e.g. a GIF downloaded or embedded in memory.
+    pub fn from_bytes(data: &[u8]) -> Self {
+        GifBuffer {
+            buffer: data.into(),
+            size: data.len(),
+            pointer: 0,
         }
     }
 
@@ -49,32 +51,309 @@ impl GifBuffer {
         self.size
     }
 
-    pub fn read_u8(&mut self) -> u8 {
-        let sl = self.buffer[self.pointer].to_owned();
-        self.pointer += 1;
-        sl
+    /// The whole input, independent of the current position, for parsing blocks this crate doesn't know about.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer
     }
 
-    pub fn read_le_u16(&mut self) -> u16 {
-        (self.read_u8() as u16) | ((self.read_u8() as u16) << 8)
+    /// Remembers the current position, to be handed to [`GifBuffer::bytes_since`].
+    pub fn mark(&self) -> usize {
+        self.pointer
     }
 
-    pub fn read_u32(&mut self) -> u32 {
-        ((self.read_u16() as u32) << 15) | (self.read_u16() as u32)
+    /// Number of bytes consumed since `mark`, used to cross-check declared block sizes.
+    /// A mark past the current position, e.g. one taken before a [`GifBuffer::reset`], counts as 0.
+    pub fn bytes_since(&self, mark: usize) -> usize {
+        self.pointer.saturating_sub(mark)
     }
 
-    pub fn read_u16(&mut self) -> u16 {
-        ((self.read_u8() as u16) << 7) | (self.read_u8() as u16)
+    /// Classic 16 bytes per line offset/hex/ASCII dump of `len` bytes from `start`, clamped to the buffer,
+    /// e.g. to look at the bytes around a block that failed to parse.
+    pub fn hexdump(&self, start: usize, len: usize) -> String {
+        let start = start.min(self.size);
+        let end = start.saturating_add(len).min(self.size);
+        let mut dump = String::new();
+        for (line, chunk) in self.buffer[start..end].chunks(16).enumerate() {
+            dump.push_str(&format!("{:08x} ", start + line * 16));
+            for column in 0..16 {
+                if column == 8 {
+                    dump.push(' ');
+                }
+                match chunk.get(column) {
+                    Some(byte) => dump.push_str(&format!(" {byte:02x}")),
+                    None => dump.push_str("   "),
+                }
+            }
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| match byte {
+                    0x20..=0x7E => byte as char,
+                    _ => '.',
+                })
+                .collect();
+            dump.push_str(&format!("  |{ascii}|\n"));
+        }
+        dump
     }
-    pub fn skip_u8(&mut self) {
+
+    /// Moves the pointer back to the start so the loaded bytes can be parsed again,
+    /// e.g. a metadata pass followed by a full decode, without re-reading the file.
+    pub fn reset(&mut self) {
+        self.pointer = 0;
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, GifError> {
+        let sl = self.peek_u8()?;
         self.pointer += 1;
+        Ok(sl)
     }
-    pub fn peek_u8(&self) -> u8 {
-        self.buffer[self.pointer]
+
+    /// Reads an unsigned little endian u16, the layout of every multi-byte field in GIF.
+    /// No field of the format is signed: widths, positions and delays all use the full `0..=65535` range.
+    pub fn read_le_u16(&mut self) -> Result<u16, GifError> {
+        Ok((self.read_u8()? as u16) | ((self.read_u8()? as u16) << 8))
+    }
+
+    /// Reads a two's complement little endian i16, for vendor extensions with signed fields.
+    pub fn read_le_i16(&mut self) -> Result<i16, GifError> {
+        Ok(self.read_le_u16()? as i16)
     }
-    pub fn read_slice(&mut self, bytes: usize) -> Vec<u8> {
-        let sl = self.buffer[self.pointer..self.pointer + bytes].to_owned();
+
+    /// Reads a big endian u32; GIF itself has no such field.
+    pub fn read_u32(&mut self) -> Result<u32, GifError> {
+        Ok(((self.read_u16()? as u32) << 16) | (self.read_u16()? as u32))
+    }
+
+    /// Reads a big endian u16; GIF fields are little endian, see [`GifBuffer::read_le_u16`].
+    pub fn read_u16(&mut self) -> Result<u16, GifError> {
+        Ok(((self.read_u8()? as u16) << 8) | (self.read_u8()? as u16))
+    }
+    /// Advances the pointer by `n` bytes, failing without moving it if fewer than `n` bytes are left.
+    pub fn skip(&mut self, n: usize) -> Result<(), GifError> {
+        if n > self.size.saturating_sub(self.pointer) {
+            return Err(GifError::UnexpectedEof { offset: self.size });
+        }
+        self.pointer += n;
+        Ok(())
+    }
+
+    pub fn skip_u8(&mut self) -> Result<(), GifError> {
+        self.skip(1)
+    }
+    pub fn peek_u8(&self) -> Result<u8, GifError> {
+        self.buffer
+            .get(self.pointer)
+            .copied()
+            .ok_or(GifError::UnexpectedEof {
+                offset: self.pointer,
+            })
+    }
+
+    /// Reads the byte at the absolute `offset` without moving the pointer.
+    pub fn read_u8_at(&self, offset: usize) -> Result<u8, GifError> {
+        self.buffer
+            .get(offset)
+            .copied()
+            .ok_or(GifError::UnexpectedEof { offset })
+    }
+
+    /// Reads the little endian u16 at the absolute `offset` without moving the pointer,
+    /// e.g. to check a length field before consuming the block it belongs to.
+    pub fn read_le_u16_at(&self, offset: usize) -> Result<u16, GifError> {
+        let low = self.read_u8_at(offset)?;
+        let high = self.read_u8_at(offset.saturating_add(1))?;
+        Ok(u16::from_le_bytes([low, high]))
+    }
+
+    pub fn read_slice(&mut self, bytes: usize) -> Result<Vec<u8>, GifError> {
+        Ok(self.read_slice_ref(bytes)?.to_owned())
+    }
+
+    /// Like [`GifBuffer::read_slice`], but borrows the bytes from the buffer instead of copying them.
+    pub fn read_slice_ref(&mut self, bytes: usize) -> Result<&[u8], GifError> {
+        let sl = self
+            .pointer
+            .checked_add(bytes)
+            .and_then(|end| self.buffer.get(self.pointer..end))
+            .ok_or(GifError::UnexpectedEof { offset: self.size })?;
         self.pointer += bytes;
-        sl
+        Ok(sl)
+    }
+
+    /// Copies the next `dst.len()` bytes into `dst` without allocating, e.g. a fixed header into a stack array.
+    /// Nothing is consumed if fewer bytes are left.
+    pub fn read_exact_into(&mut self, dst: &mut [u8]) -> Result<(), GifError> {
+        dst.copy_from_slice(self.read_slice_ref(dst.len())?);
+        Ok(())
+    }
+
+    /// Reads the bytes up to the next `byte`, like a null-terminated string of a vendor extension.
+    /// The sentinel is consumed but not returned; without one nothing is consumed.
+    pub fn read_until(&mut self, byte: u8) -> Result<Vec<u8>, GifError> {
+        let length = self.buffer[self.pointer.min(self.size)..]
+            .iter()
+            .position(|&b| b == byte)
+            .ok_or(GifError::UnexpectedEof { offset: self.size })?;
+        let sl = self.read_slice(length)?;
+        self.pointer += 1;
+        Ok(sl)
     }
+
+    /// Reads a chain of data sub-blocks and concatenates their payloads.
+    /// Each sub-block starts with a size byte (1..=255); a size of 0 is the block terminator.
+    pub fn read_data_sub_blocks(&mut self) -> Result<Vec<u8>, GifError> {
+        let mut data: Vec<u8> = Vec::new();
+        loop {
+            let block_size = self.read_u8()? as usize;
+            if block_size == 0 {
+                break;
+            }
+            data.extend_from_slice(self.read_slice_ref(block_size)?);
+        }
+        Ok(data)
+    }
+
+    /// Reads a chain of data sub-blocks like [`GifBuffer::read_data_sub_blocks`], but keeps the size bytes.
+    /// This restores payloads that are written raw and only parse as sub-blocks thanks to a trailer, like XMP.
+    pub fn read_data_sub_blocks_raw(&mut self) -> Result<Vec<u8>, GifError> {
+        let mut data: Vec<u8> = Vec::new();
+        loop {
+            let block_size = self.read_u8()?;
+            if block_size == 0 {
+                break;
+            }
+            data.push(block_size);
+            data.extend_from_slice(self.read_slice_ref(block_size as usize)?);
+        }
+        Ok(data)
+    }
+
+    /// Steps over a chain of data sub-blocks using their size bytes, without copying the payloads.
+    pub fn skip_data_sub_blocks(&mut self) -> Result<(), GifError> {
+        loop {
+            let block_size = self.read_u8()? as usize;
+            if block_size == 0 {
+                return Ok(());
+            }
+            self.skip(block_size)?;
+        }
+    }
+}
+
+/// The reads the block parsers are written against, so they run unchanged on any source of bytes:
+/// a [`GifBuffer`], a window over a file, or a future async reader.
+/// Only the first four methods are required; the rest are built on top of them.
+pub trait ByteSource {
+    /// Consumes the next byte.
+    fn read_u8(&mut self) -> Result<u8, GifError>;
+
+    /// Returns the next byte without consuming it.
+    fn peek_u8(&mut self) -> Result<u8, GifError>;
+
+    /// Consumes exactly `out.len()` bytes into `out`.
+    fn read_exact(&mut self, out: &mut [u8]) -> Result<(), GifError>;
+
+    /// Number of bytes consumed from the start of the source, reported as the offset of errors.
+    fn position(&self) -> usize;
+
+    /// Reads an unsigned little endian u16, see [`GifBuffer::read_le_u16`].
+    fn read_le_u16(&mut self) -> Result<u16, GifError> {
+        let mut bytes = [0u8; 2];
+        self.read_exact(&mut bytes)?;
+        Ok(u16::from_le_bytes(bytes))
+    }
+
+    /// Remembers the current position, to be handed to [`ByteSource::bytes_since`].
+    fn mark(&self) -> usize {
+        self.position()
+    }
+
+    /// Number of bytes consumed since `mark`; a mark past the current position counts as 0, like [`GifBuffer::bytes_since`].
+    fn bytes_since(&self, mark: usize) -> usize {
+        self.position().saturating_sub(mark)
+    }
+
+    /// Reads a chain of data sub-blocks and concatenates their payloads.
+    fn read_data_sub_blocks(&mut self) -> Result<Vec<u8>, GifError> {
+        let mut data: Vec<u8> = Vec::new();
+        loop {
+            let block_size = self.read_u8()? as usize;
+            if block_size == 0 {
+                return Ok(data);
+            }
+            let start = data.len();
+            data.resize(start + block_size, 0);
+            self.read_exact(&mut data[start..])?;
+        }
+    }
+
+    /// Reads a chain of data sub-blocks, keeping the size bytes, see [`GifBuffer::read_data_sub_blocks_raw`].
+    fn read_data_sub_blocks_raw(&mut self) -> Result<Vec<u8>, GifError> {
+        let mut data: Vec<u8> = Vec::new();
+        loop {
+            let block_size = self.read_u8()?;
+            if block_size == 0 {
+                return Ok(data);
+            }
+            data.push(block_size);
+            let start = data.len();
+            data.resize(start + block_size as usize, 0);
+            self.read_exact(&mut data[start..])?;
+        }
+    }
+
+    /// Steps over a chain of data sub-blocks.
+    fn skip_data_sub_blocks(&mut self) -> Result<(), GifError> {
+        let mut sub_block = [0u8; u8::MAX as usize];
+        loop {
+            let block_size = self.read_u8()? as usize;
+            if block_size == 0 {
+                return Ok(());
+            }
+            self.read_exact(&mut sub_block[..block_size])?;
+        }
+    }
+}
+
+impl ByteSource for GifBuffer {
+    fn read_u8(&mut self) -> Result<u8, GifError> {
+        GifBuffer::read_u8(self)
+    }
+
+    fn peek_u8(&mut self) -> Result<u8, GifError> {
+        GifBuffer::peek_u8(self)
+    }
+
+    fn read_exact(&mut self, out: &mut [u8]) -> Result<(), GifError> {
+        self.read_exact_into(out)
+    }
+
+    fn position(&self) -> usize {
+        self.pointer
+    }
+
+    fn read_le_u16(&mut self) -> Result<u16, GifError> {
+        GifBuffer::read_le_u16(self)
+    }
+
+    fn read_data_sub_blocks(&mut self) -> Result<Vec<u8>, GifError> {
+        GifBuffer::read_data_sub_blocks(self)
+    }
+
+    fn read_data_sub_blocks_raw(&mut self) -> Result<Vec<u8>, GifError> {
+        GifBuffer::read_data_sub_blocks_raw(self)
+    }
+
+    fn skip_data_sub_blocks(&mut self) -> Result<(), GifError> {
+        GifBuffer::skip_data_sub_blocks(self)
+    }
+}
+
+/// A block of the GIF Data Stream that can be parsed from any [`ByteSource`] on its own.
+/// Blocks whose layout depends on earlier blocks, like color tables and raster data, take their context as extra arguments
+/// and read from a [`GifBuffer`] instead.
+pub trait GifBlock {
+    fn parse<S: ByteSource + ?Sized>(buf: &mut S) -> Result<Self, GifError>
+    where
+        Self: Sized;
 }
@@ -0,0 +1,170 @@
+use crate::error::GifError;
+use crate::extension::{DisposalMethod, GraphicControlExtension};
+use crate::gif::{DescriptorGroup, Gif, GlobalColorMap, LogicalScreenDescriptor};
+
+/// Largest pixel buffer the renderers allocate, 1 GiB: a 16384x16384 RGBA canvas.
+/// Checked before allocating, so a stream declaring a huge Logical Screen fails the same way on every host
+/// instead of depending on how much memory the allocator is willing to hand out.
+pub const MAX_PIXEL_BUFFER_BYTES: usize = 1 << 30;
+
+/// An RGBA image covering the Logical Screen that frames are drawn onto.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canvas {
+    width: u16,
+    height: u16,
+    /// r, g, b, a bytes of every pixel, left to right and top to bottom.
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    /// A fully transparent canvas, or `ImageTooLarge` instead of aborting when it is larger than
+    /// [`MAX_PIXEL_BUFFER_BYTES`] or can't be allocated.
+    pub fn try_new(width: u16, height: u16) -> Result<Self, GifError> {
+        Ok(Canvas {
+            width,
+            height,
+            pixels: pixel_buffer(width, height, 4, None)?,
+        })
+    }
+
+    /// A fully transparent canvas of the Logical Screen, failing with the offset of its descriptor.
+    pub(crate) fn for_screen(
+        screen_descriptor: &LogicalScreenDescriptor,
+    ) -> Result<Self, GifError> {
+        let width = screen_descriptor.logical_screen_width();
+        let height = screen_descriptor.logical_screen_height();
+        Ok(Canvas {
+            width,
+            height,
+            pixels: pixel_buffer(width, height, 4, Some(screen_descriptor.offset))?,
+        })
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Draws a frame at its offset as opaque pixels; transparent pixels keep what is underneath.
+    pub fn draw(&mut self, group: &DescriptorGroup, global_color_map: Option<&GlobalColorMap>) {
+        let pixels = &mut self.pixels;
+        group.for_each_visible_pixel(
+            self.width,
+            self.height,
+            global_color_map,
+            |position, color| {
+                if let Some(pixel) = pixels.get_mut(position * 4..position * 4 + 4) {
+                    pixel[..3].copy_from_slice(color);
+                    pixel[3] = u8::MAX;
+                }
+            },
+        );
+    }
+
+    /// Restores the area covered by a frame to the background.
+    /// Like browsers, the background is transparent rather than the Background Color.
+    fn clear(&mut self, group: &DescriptorGroup) {
+        let (left, top, width, height) = group.image_descriptor.rect();
+        let right = (left as usize + width as usize).min(self.width as usize);
+        let bottom = (top as usize + height as usize).min(self.height as usize);
+        for y in top as usize..bottom {
+            for x in left as usize..right {
+                let offset = (y * self.width as usize + x) * 4;
+                self.pixels[offset..offset + 4].fill(0);
+            }
+        }
+    }
+}
+
+/// Replays the frames of a GIF in order, yielding the RGBA canvas as displayed after each frame.
+///
+/// Before a frame is drawn, the previous one is disposed of as its Graphic Control Extension asks:
+/// Restore to background clears its area, Restore to previous puts back the canvas from before it was drawn,
+/// and every other value leaves it in place.
+pub struct Compositor<'a> {
+    gif: &'a Gif,
+    canvas: Canvas,
+    index: usize,
+    /// The canvas before the last drawn frame, kept only when that frame restores to previous.
+    previous_pixels: Option<Vec<u8>>,
+}
+
+impl<'a> Compositor<'a> {
+    /// Fails with `ImageTooLarge` when the canvas of the Logical Screen is too large to allocate.
+    pub fn new(gif: &'a Gif) -> Result<Self, GifError> {
+        Ok(Compositor {
+            gif,
+            canvas: Canvas::for_screen(&gif.logical_screen_descriptor)?,
+            index: 0,
+            previous_pixels: None,
+        })
+    }
+}
+
+impl Iterator for Compositor<'_> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let descriptor_groups = &self.gif.descriptor_groups;
+        if let Some(previous) = self.index.checked_sub(1).map(|i| &descriptor_groups[i]) {
+            match disposal_method(previous) {
+                DisposalMethod::RestoreBackground => self.canvas.clear(previous),
+                DisposalMethod::RestorePrevious => {
+                    if let Some(pixels) = self.previous_pixels.take() {
+                        self.canvas.pixels = pixels;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let group = descriptor_groups.get(self.index)?;
+        self.previous_pixels = (disposal_method(group) == DisposalMethod::RestorePrevious)
+            .then(|| self.canvas.pixels.clone());
+        self.canvas.draw(group, self.gif.global_color_map.as_ref());
+        self.index += 1;
+        Some(self.canvas.pixels.clone())
+    }
+}
+
+/// Allocates a zeroed `width` x `height` image of `channels` bytes per pixel, computing its size in `usize`.
+/// A size past [`MAX_PIXEL_BUFFER_BYTES`] or a failed allocation is reported as `ImageTooLarge` at `offset` rather than panicking.
+pub(crate) fn pixel_buffer(
+    width: u16,
+    height: u16,
+    channels: usize,
+    offset: Option<usize>,
+) -> Result<Vec<u8>, GifError> {
+    let too_large = || GifError::ImageTooLarge {
+        width,
+        height,
+        offset,
+    };
+    let size = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|area| area.checked_mul(channels))
+        .filter(|&size| size <= MAX_PIXEL_BUFFER_BYTES)
+        .ok_or_else(too_large)?;
+    let mut buffer: Vec<u8> = Vec::new();
+    buffer.try_reserve_exact(size).map_err(|_| too_large())?;
+    buffer.resize(size, 0);
+    Ok(buffer)
+}
+
+fn disposal_method(group: &DescriptorGroup) -> DisposalMethod {
+    group.graphic_control_extension.as_ref().map_or(
+        DisposalMethod::None,
+        GraphicControlExtension::disposal_method,
+    )
+}
@@ -0,0 +1,79 @@
+use crate::palette::{self, Palette};
+use wide::u32x4;
+
+/// Resolves color table indices into packed RGB triples sixteen pixels at a time.
+/// Produces the same bytes as the scalar [`palette::expand_rgb`]: indices outside the table are black.
+///
+/// Every pixel is looked up as a little endian `r | g << 8 | b << 16` word. The sixteen pixels are
+/// split into four groups of four; lane `g` of vector `k` holds pixel `k` of group `g`, so one shift and
+/// or per output word packs all four groups into their 12 bytes at once.
+pub(crate) fn expand_rgb(indices: &[u8], palette: Option<&Palette>) -> Vec<u8> {
+    // a full table, so the loop needs neither bounds nor missing-color checks
+    let mut table = [0u32; 256];
+    if let Some(palette) = palette {
+        for (word, &[r, g, b]) in table.iter_mut().zip(palette.entries()) {
+            *word = u32::from_le_bytes([r, g, b, 0]);
+        }
+    }
+
+    let index_chunks = indices.chunks_exact(16);
+    let remainder = index_chunks.remainder();
+    let mut rgb: Vec<u8> = vec![0u8; (indices.len() - remainder.len()) * 3];
+    let mut rgb_chunks = rgb.chunks_exact_mut(48);
+    for (chunk, out) in index_chunks.zip(&mut rgb_chunks) {
+        let pixel = |k: usize| {
+            u32x4::new([
+                table[chunk[k] as usize],
+                table[chunk[4 + k] as usize],
+                table[chunk[8 + k] as usize],
+                table[chunk[12 + k] as usize],
+            ])
+        };
+        let (p0, p1, p2, p3) = (pixel(0), pixel(1), pixel(2), pixel(3));
+        let words = [
+            (p0 | (p1 << 24_u32)).to_array(),
+            ((p1 >> 8_u32) | (p2 << 16_u32)).to_array(),
+            ((p2 >> 16_u32) | (p3 << 8_u32)).to_array(),
+        ];
+        for (group, out) in out.chunks_exact_mut(12).enumerate() {
+            for (word, out) in words.iter().zip(out.chunks_exact_mut(4)) {
+                out.copy_from_slice(&word[group].to_le_bytes());
+            }
+        }
+    }
+
+    // the last up to 15 pixels don't fill a set of vectors
+    rgb.extend(palette::expand_rgb(remainder, palette));
+    rgb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::GifBuffer;
+
+    fn palette(len: usize) -> Palette {
+        let bytes: Vec<u8> = (0..len * 3).map(|byte| (byte * 7 % 256) as u8).collect();
+        Palette::parse(&mut GifBuffer::from_bytes(&bytes), len).unwrap()
+    }
+
+    /// Every length around the 16 pixel vector width, with indices inside and past the table and without a table.
+    #[test]
+    fn simd_matches_scalar() {
+        for palette in [
+            None,
+            Some(palette(2)),
+            Some(palette(16)),
+            Some(palette(256)),
+        ] {
+            for len in 0..100 {
+                let indices: Vec<u8> = (0..len).map(|i| (i * 37 % 256) as u8).collect();
+                assert_eq!(
+                    expand_rgb(&indices, palette.as_ref()),
+                    palette::expand_rgb(&indices, palette.as_ref()),
+                    "{len} indices"
+                );
+            }
+        }
+    }
+}
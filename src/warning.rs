@@ -0,0 +1,40 @@
+use std::fmt;
+
+/// Recoverable oddities found while decoding; unlike a [`GifError`](crate::error::GifError) they don't stop the decode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum GifWarning {
+    /// Reserved bits of the Image Descriptor or Graphic Control Extension of frame `frame` are not 0.
+    ReservedBitsSet { frame: usize },
+
+    /// The Delay Time of frame `frame` is below `DecodeOptions::min_delay` and is raised to it during playback.
+    DelayClamped { frame: usize, delay: u16 },
+
+    /// The stream ended before the Trailer; only the `frame_count` complete frames were kept.
+    Truncated { frame_count: usize },
+
+    /// `count` bytes follow the Trailer and were ignored.
+    TrailingBytes { count: usize },
+}
+
+impl fmt::Display for GifWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GifWarning::ReservedBitsSet { frame } => {
+                write!(f, "frame {frame} has reserved bits set")
+            }
+            GifWarning::DelayClamped { frame, delay } => {
+                write!(f, "frame {frame} delay of {delay} is below the minimum")
+            }
+            GifWarning::Truncated { frame_count } => {
+                write!(
+                    f,
+                    "stream ends before the trailer, kept {frame_count} complete frame(s)"
+                )
+            }
+            GifWarning::TrailingBytes { count } => {
+                write!(f, "{count} bytes after the trailer were ignored")
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::{frame, gif, netscape_loop, palette, temp_file, Frame};
+use gif_parser::animation::Animation;
+use gif_parser::gif::Gif;
+use gif_parser::options::DecodeOptions;
+use gif_parser::warning::GifWarning;
+use std::time::Duration;
+
+/// One pixel frame with a Graphic Control Extension holding `delay_time`.
+fn delayed(delay_time: u16) -> Frame {
+    Frame {
+        graphic_control: Some((0, delay_time, None)),
+        ..frame(0, 0, 1, 1, vec![0])
+    }
+}
+
+/// Delays of 0, 5 and 0 hundredths.
+fn zero_delays() -> Vec<u8> {
+    gif(
+        1,
+        1,
+        Some(palette()),
+        &[delayed(0), delayed(5), delayed(0)],
+        &[],
+    )
+}
+
+/// `Animation::with_options` raises delays below `min_delay`; decoding with the option only adds warnings,
+/// the frames and their Delay Times are those of a plain decode.
+#[test]
+fn min_delay_clamps_playback() {
+    let path = temp_file("min-delay.gif", &zero_delays());
+    let options = DecodeOptions::new().min_delay(2);
+    let gif = Gif::decode_with_options(path.to_str().unwrap(), &options).unwrap();
+    let plain = Gif::decode_bytes(&zero_delays()).unwrap();
+
+    let clamped = Animation::with_options(&gif, &options);
+    assert_eq!(
+        clamped.frame_delays(),
+        [20, 50, 20].map(Duration::from_millis)
+    );
+    assert_eq!(clamped.total_duration(), Duration::from_millis(90));
+    assert_eq!(clamped.frame_at(Duration::from_millis(75)), Some(2));
+    assert!(gif
+        .warnings
+        .contains(&GifWarning::DelayClamped { frame: 0, delay: 0 }));
+
+    assert_eq!(gif.descriptor_groups, plain.descriptor_groups);
+    assert_eq!(gif.animation(), plain.animation());
+    assert_eq!(gif.animation().total_duration(), Duration::from_millis(50));
+    assert_eq!(
+        Animation::with_options(&plain, &DecodeOptions::new().min_delay(10)).total_duration(),
+        Duration::from_millis(300)
+    );
+}
+
+/// Two frames of 100ms each, with the given extensions in front of them.
+fn two_frames(extensions: &[u8]) -> Gif {
+    Gif::decode_bytes(&gif(
+        1,
+        1,
+        Some(palette()),
+        &[delayed(10), delayed(10)],
+        extensions,
+    ))
+    .unwrap()
+}
+
+/// An infinite loop wraps the elapsed time around the total duration; without a loop count
+/// the last frame stays on screen once the single play is over.
+#[test]
+fn frame_at_time_wraps_infinite_loops() {
+    let looping = two_frames(&netscape_loop(0));
+    assert_eq!(looping.frame_at_time(0), Some(0));
+    assert_eq!(looping.frame_at_time(99), Some(0));
+    assert_eq!(looping.frame_at_time(150), Some(1));
+    assert_eq!(looping.frame_at_time(250), Some(0));
+    assert_eq!(looping.frame_at_time(1_000_150), Some(1));
+
+    let once = two_frames(&[]);
+    assert_eq!(once.frame_at_time(150), Some(1));
+    assert_eq!(once.frame_at_time(250), Some(1));
+
+    // looping twice more plays three times in total
+    let three_plays = two_frames(&netscape_loop(2));
+    assert_eq!(three_plays.frame_at_time(450), Some(0));
+    assert_eq!(three_plays.frame_at_time(650), Some(1));
+}
+
+/// Every frame starts where the delays of the frames before it end.
+#[test]
+fn frame_timeline_accumulates_delays() {
+    let gif = Gif::decode_bytes(&gif(
+        1,
+        1,
+        Some(palette()),
+        &[delayed(10), delayed(10), delayed(10)],
+        &[],
+    ))
+    .unwrap();
+
+    assert_eq!(
+        gif.animation().to_frame_timeline(),
+        vec![
+            (0, Duration::ZERO),
+            (1, Duration::from_millis(100)),
+            (2, Duration::from_millis(200)),
+        ]
+    );
+}
+
+/// Only a loop count of 0 loops forever; a count of 3 and a missing extension both end.
+#[test]
+fn infinite_loop_needs_a_zero_loop_count() {
+    let loops = |extensions: &[u8]| Animation::new(&two_frames(extensions)).is_infinite_loop();
+    assert!(!loops(&netscape_loop(3)));
+    assert!(loops(&netscape_loop(0)));
+    assert!(!loops(&[]));
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "apng")]
+
+mod common;
+
+use gif_parser::gif::Gif;
+use std::fs::File;
+use std::io::BufReader;
+
+/// Reloading the APNG gives back the frame count, loop count, delays and composited pixels.
+#[test]
+fn save_apng_reloads() {
+    let frames = [
+        common::Frame {
+            graphic_control: Some((0, 10, None)),
+            ..common::frame(0, 0, 2, 1, vec![0, 1])
+        },
+        common::Frame {
+            graphic_control: Some((0, 25, Some(0))),
+            ..common::frame(1, 0, 1, 1, vec![2])
+        },
+    ];
+    let data = common::gif(
+        2,
+        1,
+        Some(common::palette()),
+        &frames,
+        &common::netscape_loop(2),
+    );
+    let gif = Gif::decode_bytes(&data).unwrap();
+    let path = std::env::temp_dir().join(format!("gif-parser-{}-reload.png", std::process::id()));
+    gif.save_apng(path.to_str().unwrap()).unwrap();
+
+    let decoder = png::Decoder::new(BufReader::new(File::open(&path).unwrap()));
+    let mut reader = decoder.read_info().unwrap();
+    let animation_control = reader.info().animation_control.unwrap();
+    assert_eq!(animation_control.num_frames, 2);
+    // the NETSCAPE2.0 loop count repeats after the first play
+    assert_eq!(animation_control.num_plays, 3);
+
+    let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+    for (expected, delay) in gif.rgba_frames().unwrap().iter().zip([10, 25]) {
+        reader.next_frame(&mut pixels).unwrap();
+        let frame_control = reader.info().frame_control.unwrap();
+        assert_eq!(
+            (frame_control.delay_num, frame_control.delay_den),
+            (delay, 100)
+        );
+        assert_eq!(&pixels[..expected.len()], expected);
+    }
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,325 @@
+mod common;
+
+use gif_parser::error::GifError;
+use gif_parser::gif::{Gif, GifSignature, ImageDescriptor, LogicalScreenDescriptor};
+use gif_parser::parser::{ByteSource, GifBlock, GifBuffer};
+
+/// A length that runs past the end of the address space is an `UnexpectedEof` like any other short read.
+#[test]
+fn huge_read_is_unexpected_eof() {
+    let mut buf = GifBuffer::from_bytes(b"GIF89a");
+    buf.read_u8().unwrap();
+    assert!(matches!(
+        buf.read_slice_ref(usize::MAX),
+        Err(GifError::UnexpectedEof { offset: 6 })
+    ));
+    assert!(matches!(
+        buf.read_slice(usize::MAX),
+        Err(GifError::UnexpectedEof { offset: 6 })
+    ));
+    assert_eq!(buf.get_pointer(), 1);
+}
+
+/// A mark taken before `reset` lies ahead of the rewound position and must not underflow.
+#[test]
+fn bytes_since_mark_before_reset() {
+    let mut buf = GifBuffer::from_bytes(b"GIF89a");
+    buf.read_slice_ref(4).unwrap();
+    let mark = buf.mark();
+    buf.read_u8().unwrap();
+    assert_eq!(buf.bytes_since(mark), 1);
+    buf.reset();
+    assert_eq!(buf.bytes_since(mark), 0);
+}
+
+/// After `reset` the same buffer parses to the same blocks again.
+#[test]
+fn reset_parses_again() {
+    let mut buf = GifBuffer::read(&common::fixture("stars.gif")).unwrap();
+    let signature = GifSignature::parse(&mut buf).unwrap();
+    let logical_screen_descriptor = LogicalScreenDescriptor::parse(&mut buf).unwrap();
+    assert_eq!(buf.get_pointer(), 13);
+
+    buf.reset();
+    assert_eq!(buf.get_pointer(), 0);
+    assert_eq!(GifSignature::parse(&mut buf).unwrap(), signature);
+    assert_eq!(
+        LogicalScreenDescriptor::parse(&mut buf).unwrap(),
+        logical_screen_descriptor
+    );
+}
+
+/// Two full 255 byte sub-blocks and a partial one are chained until the zero length Block Terminator.
+#[test]
+fn full_sub_blocks_are_chained() {
+    let payload: Vec<u8> = (0..255 + 255 + 10).map(|byte| byte as u8).collect();
+    let mut data = common::sub_blocks(&payload);
+    assert_eq!((data[0], data[256], data[512]), (255, 255, 10));
+    data.push(0x3B);
+
+    let mut buf = GifBuffer::from_bytes(&data);
+    assert_eq!(buf.read_data_sub_blocks().unwrap(), payload);
+    assert_eq!(buf.get_pointer(), data.len() - 1);
+}
+
+/// Parses any block generically, through the trait alone.
+fn parse_block<T: GifBlock>(data: &[u8]) -> Result<T, GifError> {
+    T::parse(&mut GifBuffer::from_bytes(data))
+}
+
+/// `GifSignature` parses through `GifBlock` and reports a bad signature or version as an error.
+#[test]
+fn signature_through_gif_block() {
+    let signature: GifSignature = parse_block(b"GIF89a").unwrap();
+    assert_eq!(signature.magic(), "GIF");
+    assert_ne!(parse_block::<GifSignature>(b"GIF87a").unwrap(), signature);
+    assert!(matches!(
+        parse_block::<GifSignature>(b"PNG89a"),
+        Err(GifError::InvalidSignature { offset: 0, .. })
+    ));
+    assert!(matches!(
+        parse_block::<GifSignature>(b"GIF88a"),
+        Err(GifError::UnknownVersion { offset: 3, .. })
+    ));
+    assert!(matches!(
+        parse_block::<GifSignature>(b"GIF8"),
+        Err(GifError::UnexpectedEof { .. })
+    ));
+}
+
+/// A lowercase `gif` Signature is accepted and kept as `GIF`, so the stream encodes back with the usual magic.
+#[test]
+fn lowercase_magic_is_accepted() {
+    let signature: GifSignature = parse_block(b"gif89a").unwrap();
+    assert_eq!(signature.magic(), "GIF");
+    assert_eq!(signature, parse_block(b"GIF89a").unwrap());
+    assert_eq!(
+        parse_block::<GifSignature>(b"gIf87a").unwrap().magic(),
+        "GIF"
+    );
+
+    let mut data = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![1])],
+        &[],
+    );
+    data[..3].copy_from_slice(b"gif");
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(&gif.encode()[..6], b"GIF89a");
+}
+
+/// The pixel count of a 40000x40000 Image Descriptor is computed without `u16` overflow.
+#[test]
+fn image_descriptor_area_does_not_overflow() {
+    let mut data = vec![0x2C, 0, 0, 0, 0];
+    data.extend(40000u16.to_le_bytes());
+    data.extend(40000u16.to_le_bytes());
+    data.push(0);
+    let image_descriptor = ImageDescriptor::parse(&mut GifBuffer::from_bytes(&data)).unwrap();
+    assert_eq!(image_descriptor.area(), 1_600_000_000);
+    assert_eq!(image_descriptor.byte_size(1), 1_600_000_000);
+    assert_eq!(image_descriptor.byte_size(4), 6_400_000_000);
+}
+
+/// `read_until` stops at the sentinel and leaves the pointer after it; without a sentinel nothing is consumed.
+#[test]
+fn read_until_sentinel() {
+    let mut buf = GifBuffer::from_bytes(b"abc\0def");
+    assert_eq!(buf.read_until(0).unwrap(), b"abc");
+    assert_eq!(buf.get_pointer(), 4);
+    assert!(matches!(
+        buf.read_until(0),
+        Err(GifError::UnexpectedEof { offset: 7 })
+    ));
+    assert_eq!(buf.get_pointer(), 4);
+    assert_eq!(buf.read_until(b'f').unwrap(), b"de");
+    assert_eq!(buf.get_pointer(), 7);
+    assert!(buf.read_until(0).is_err());
+}
+
+/// A 16 byte region dumps as one line of offset, hex columns split in two halves, and ASCII;
+/// a region running past the end is clamped.
+#[test]
+fn hexdump_of_a_known_region() {
+    let buf = GifBuffer::from_bytes(b"GIF89a\x0a\x00\x0a\x00\x91\x00\x00\xff\xff\xff-tail");
+    assert_eq!(
+        buf.hexdump(0, 16),
+        "00000000  47 49 46 38 39 61 0a 00  0a 00 91 00 00 ff ff ff  |GIF89a..........|\n"
+    );
+    assert_eq!(
+        buf.hexdump(16, 100),
+        "00000010  2d 74 61 69 6c                                    |-tail|\n"
+    );
+    assert_eq!(buf.hexdump(100, 16), "");
+}
+
+/// `as_slice` is the whole input whatever the position, so custom parsers can index it directly.
+#[test]
+fn as_slice_is_the_whole_input() {
+    let path = common::fixture("stars.gif");
+    let mut buf = GifBuffer::read(&path).unwrap();
+    assert_eq!(buf.as_slice().len(), buf.get_size());
+    assert_eq!(buf.as_slice(), std::fs::read(&path).unwrap());
+
+    GifSignature::parse(&mut buf).unwrap();
+    assert_eq!(buf.as_slice().len(), buf.get_size());
+    assert_eq!(&buf.as_slice()[..buf.get_pointer()], b"GIF87a");
+}
+
+/// Positional reads look anywhere in the input without moving the pointer; past the end they are `UnexpectedEof`.
+#[test]
+fn positional_reads_keep_the_pointer() {
+    let mut buf = GifBuffer::from_bytes(b"GIF89a\x2c\x01\xc8\x00");
+    buf.read_slice_ref(3).unwrap();
+    // Logical Screen Width of 300 and Height of 200
+    assert_eq!(buf.read_le_u16_at(6).unwrap(), 300);
+    assert_eq!(buf.read_le_u16_at(8).unwrap(), 200);
+    assert_eq!(buf.read_u8_at(0).unwrap(), b'G');
+    assert_eq!(buf.get_pointer(), 3);
+    assert!(matches!(
+        buf.read_le_u16_at(9),
+        Err(GifError::UnexpectedEof { offset: 10 })
+    ));
+    assert!(matches!(
+        buf.read_u8_at(usize::MAX),
+        Err(GifError::UnexpectedEof { offset: usize::MAX })
+    ));
+    assert_eq!(buf.get_pointer(), 3);
+    assert_eq!(buf.read_u8().unwrap(), b'8');
+}
+
+/// All ones is the largest unsigned value, not -1; only the explicitly signed reader sees -1.
+#[test]
+fn high_bit_is_not_a_sign() {
+    let mut buf =
+        GifBuffer::from_bytes(&[0xFF, 0xFF, 0xFF, 0xFF, 0x12, 0x34, 0x12, 0x34, 0x56, 0x78]);
+    assert_eq!(buf.read_le_u16().unwrap(), 65535);
+    assert_eq!(buf.read_le_i16().unwrap(), -1);
+    assert_eq!(buf.read_u16().unwrap(), 0x1234);
+    assert_eq!(buf.read_u32().unwrap(), 0x12345678);
+}
+
+/// The least a `ByteSource` can be: a `Vec` and a position.
+struct VecSource {
+    data: Vec<u8>,
+    position: usize,
+}
+
+impl ByteSource for VecSource {
+    fn read_u8(&mut self) -> Result<u8, GifError> {
+        let byte = self.peek_u8()?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn peek_u8(&mut self) -> Result<u8, GifError> {
+        self.data
+            .get(self.position)
+            .copied()
+            .ok_or(GifError::UnexpectedEof {
+                offset: self.position,
+            })
+    }
+
+    fn read_exact(&mut self, out: &mut [u8]) -> Result<(), GifError> {
+        for byte in out {
+            *byte = self.read_u8()?;
+        }
+        Ok(())
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// The block parsers run unchanged over a source other than `GifBuffer`, errors included.
+#[test]
+fn parse_through_a_vec_source() {
+    let data = common::gif(
+        3,
+        2,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![0])],
+        &[],
+    );
+    let mut source = VecSource {
+        data: data.clone(),
+        position: 0,
+    };
+    let mut buf = GifBuffer::from_bytes(&data);
+
+    assert_eq!(
+        GifSignature::parse(&mut source).unwrap(),
+        GifSignature::parse(&mut buf).unwrap()
+    );
+    assert_eq!(
+        LogicalScreenDescriptor::parse(&mut source).unwrap(),
+        LogicalScreenDescriptor::parse(&mut buf).unwrap()
+    );
+    assert_eq!(source.position(), 13);
+
+    let mut short = VecSource {
+        data: b"GIF8".to_vec(),
+        position: 0,
+    };
+    assert!(matches!(
+        GifSignature::parse(&mut short),
+        Err(GifError::UnexpectedEof { offset: 4 })
+    ));
+}
+
+/// The default `bytes_since` treats a mark ahead of the position as 0 instead of underflowing.
+#[test]
+fn vec_source_bytes_since_a_later_mark() {
+    let mut source = VecSource {
+        data: b"GIF89a".to_vec(),
+        position: 0,
+    };
+    let mark = source.mark();
+    source.read_u8().unwrap();
+    assert_eq!(source.bytes_since(mark), 1);
+    assert_eq!(source.bytes_since(4), 0);
+}
+
+/// Reading into a fixed array copies the next bytes and advances past them; a short read leaves the pointer alone.
+#[test]
+fn read_exact_into_an_array() {
+    let mut buf = GifBuffer::from_bytes(b"GIF89a");
+    buf.read_u8().unwrap();
+    let mut dst = [0u8; 4];
+    buf.read_exact_into(&mut dst).unwrap();
+    assert_eq!(&dst, b"IF89");
+    assert_eq!(buf.get_pointer(), 5);
+
+    assert!(matches!(
+        buf.read_exact_into(&mut dst),
+        Err(GifError::UnexpectedEof { .. })
+    ));
+    assert_eq!(buf.get_pointer(), 5);
+    buf.read_exact_into(&mut []).unwrap();
+    assert_eq!(buf.get_pointer(), 5);
+}
+
+/// Skipping one byte past the end fails at the end of the input and leaves the pointer where it was.
+#[test]
+fn skip_past_eof() {
+    let mut buf = GifBuffer::from_bytes(b"GIF89a");
+    buf.read_slice_ref(2).unwrap();
+    let len = buf.get_size() - buf.get_pointer();
+    assert!(matches!(
+        buf.skip(len + 1),
+        Err(GifError::UnexpectedEof { offset: 6 })
+    ));
+    assert_eq!(buf.get_pointer(), 2);
+
+    buf.skip(len).unwrap();
+    assert_eq!(buf.get_pointer(), 6);
+    assert!(matches!(
+        buf.skip_u8(),
+        Err(GifError::UnexpectedEof { offset: 6 })
+    ));
+    assert_eq!(buf.get_pointer(), 6);
+}
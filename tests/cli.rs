@@ -0,0 +1,138 @@
+mod common;
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn decoder(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_decoder"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// A looping animation of three frames shown for 100, 0 and 300 ms.
+fn timed_gif(name: &str) -> PathBuf {
+    let frames: Vec<common::Frame> = [10, 0, 30]
+        .into_iter()
+        .map(|delay_time| common::Frame {
+            graphic_control: Some((0, delay_time, None)),
+            ..common::frame(0, 0, 1, 1, vec![0])
+        })
+        .collect();
+    let data = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &frames,
+        &common::netscape_loop(0),
+    );
+    common::temp_file(name, &data)
+}
+
+/// `--json` prints nothing but the parsed structure, so the output can be piped into other tools.
+#[cfg(feature = "serde")]
+#[test]
+fn json_dumps_the_parsed_structure() {
+    let output = decoder(&["--json", &common::fixture("stars.gif")]);
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["signature"]["magic"], "GIF");
+    assert!(json["logical_screen_descriptor"].is_object());
+    let gif = gif_parser::gif::Gif::decode(&common::fixture("stars.gif")).unwrap();
+    assert_eq!(
+        json["descriptor_groups"].as_array().unwrap().len(),
+        gif.descriptor_groups.len()
+    );
+}
+
+/// Without the `serde` feature `--json` fails instead of printing something else.
+#[cfg(not(feature = "serde"))]
+#[test]
+fn json_needs_the_serde_feature() {
+    let output = decoder(&["--json", &common::fixture("stars.gif")]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("serde"));
+}
+
+/// `--info` prints the frame count, loop count, every delay and the total duration.
+#[test]
+fn info_prints_animation_timing() {
+    let path = timed_gif("cli-info.gif");
+    let path = path.to_str().unwrap();
+
+    let output = decoder(&["--info", path]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    for line in [
+        "INFO: Frame count: 3",
+        "INFO: Animated: true",
+        "INFO: Loop count: infinite",
+        "INFO: Frame 0 delay: 100ms",
+        "INFO: Frame 1 delay: 0ns",
+        "INFO: Frame 2 delay: 300ms",
+        "INFO: Total duration: 400ms",
+    ] {
+        assert!(stdout.contains(line), "{line:?} missing from\n{stdout}");
+    }
+}
+
+/// `--min-delay` raises the delays `--info` prints, the zero delay included, and the total with them.
+#[test]
+fn info_clamps_to_min_delay() {
+    let path = timed_gif("cli-info-min-delay.gif");
+    let path = path.to_str().unwrap();
+
+    let output = decoder(&["--info", "--min-delay", "2", path]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("INFO: Frame 1 delay: 20ms"), "{stdout}");
+    assert!(stdout.contains("INFO: Total duration: 420ms"), "{stdout}");
+}
+
+/// `--extract-frames` writes every composited frame as a zero-padded, numbered PNG.
+#[cfg(feature = "apng")]
+#[test]
+fn extract_frames_writes_one_png_per_frame() {
+    let frames = [
+        common::frame(0, 0, 2, 1, vec![0, 1]),
+        common::frame(1, 0, 1, 1, vec![2]),
+    ];
+    let data = common::gif(2, 1, Some(common::palette()), &frames, &[]);
+    let path = common::temp_file("cli-extract.gif", &data);
+    let dir = std::env::temp_dir().join(format!("gif-parser-{}-frames", std::process::id()));
+
+    let output = decoder(&[
+        "--extract-frames",
+        dir.to_str().unwrap(),
+        path.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    let mut names: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["frame_000.png", "frame_001.png"]);
+
+    let gif = gif_parser::gif::Gif::decode_bytes(&data).unwrap();
+    let decoder = png::Decoder::new(std::io::BufReader::new(
+        std::fs::File::open(dir.join("frame_001.png")).unwrap(),
+    ));
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+    reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(pixels, gif.frame_rgba(1).unwrap());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Without the `image-integration` feature, or `apng` which it enables, `--extract-frames` fails
+/// instead of writing nothing silently.
+#[cfg(not(feature = "apng"))]
+#[test]
+fn extract_frames_needs_the_apng_feature() {
+    let output = decoder(&["--extract-frames", "unused", &common::fixture("stars.gif")]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("image-integration"));
+}
@@ -0,0 +1,206 @@
+//! Builders for small hand-made GIF streams shared by the integration tests.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+/// Compresses `indices` with a Clear Code in front of every two indices, so the code size never grows
+/// past `lzw_minimum_code_size + 1`, and ends the stream with an End of Information code.
+/// Every index must be below `2^lzw_minimum_code_size`.
+pub fn lzw(lzw_minimum_code_size: u8, indices: &[u8]) -> Vec<u8> {
+    let code_size = lzw_minimum_code_size as u32 + 1;
+    let clear_code = 1u32 << lzw_minimum_code_size;
+    let mut out: Vec<u8> = Vec::new();
+    let (mut bits, mut bit_count) = (0u32, 0u32);
+    let mut put = |code: u32, out: &mut Vec<u8>| {
+        bits |= code << bit_count;
+        bit_count += code_size;
+        while bit_count >= 8 {
+            out.push(bits as u8);
+            bits >>= 8;
+            bit_count -= 8;
+        }
+    };
+    for pair in indices.chunks(2) {
+        put(clear_code, &mut out);
+        for &index in pair {
+            put(index as u32, &mut out);
+        }
+    }
+    put(clear_code + 1, &mut out);
+    if bit_count > 0 {
+        out.push(bits as u8);
+    }
+    out
+}
+
+/// Splits `data` into data sub-blocks of at most 255 bytes and appends the Block Terminator.
+pub fn sub_blocks(data: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = Vec::new();
+    for chunk in data.chunks(255) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+    out.push(0);
+    out
+}
+
+/// One image of a stream built by [`gif`].
+pub struct Frame {
+    pub left: u16,
+    pub top: u16,
+    pub width: u16,
+    pub height: u16,
+    pub indices: Vec<u8>,
+    pub local_color_table: Option<Vec<[u8; 3]>>,
+    /// `(disposal method, delay time, transparent color index)` of a Graphic Control Extension in front of the image.
+    pub graphic_control: Option<(u8, u16, Option<u8>)>,
+    pub interlaced: bool,
+}
+
+/// A frame without Local Color Table, Graphic Control Extension or interlacing.
+pub fn frame(left: u16, top: u16, width: u16, height: u16, indices: Vec<u8>) -> Frame {
+    Frame {
+        left,
+        top,
+        width,
+        height,
+        indices,
+        local_color_table: None,
+        graphic_control: None,
+        interlaced: false,
+    }
+}
+
+/// The Size of Color Table field that fits `len` colors.
+fn table_size_field(len: usize) -> u8 {
+    let mut field = 0;
+    while (2usize << field) < len {
+        field += 1;
+    }
+    field
+}
+
+fn push_color_table(out: &mut Vec<u8>, colors: &[[u8; 3]]) {
+    for index in 0..(2usize << table_size_field(colors.len())) {
+        out.extend(colors.get(index).copied().unwrap_or([0, 0, 0]));
+    }
+}
+
+/// Graphic Control Extension block with the given disposal method, delay and transparency.
+pub fn graphic_control_extension(
+    disposal_method: u8,
+    delay_time: u16,
+    transparent: Option<u8>,
+) -> Vec<u8> {
+    let mut out = vec![
+        0x21,
+        0xF9,
+        4,
+        (disposal_method << 2) | transparent.is_some() as u8,
+    ];
+    out.extend(delay_time.to_le_bytes());
+    out.extend([transparent.unwrap_or(0), 0]);
+    out
+}
+
+/// Image Descriptor, Local Color Table and Image Data of `frame`, with LZW Minimum Code Size 2.
+pub fn image(frame: &Frame) -> Vec<u8> {
+    let mut out: Vec<u8> = vec![0x2C];
+    for value in [frame.left, frame.top, frame.width, frame.height] {
+        out.extend(value.to_le_bytes());
+    }
+    let mut packed_fields = if frame.interlaced { 0x40 } else { 0 };
+    if let Some(local_color_table) = &frame.local_color_table {
+        packed_fields |= 0x80 | table_size_field(local_color_table.len());
+    }
+    out.push(packed_fields);
+    if let Some(local_color_table) = &frame.local_color_table {
+        push_color_table(&mut out, local_color_table);
+    }
+    out.push(2);
+    out.extend(sub_blocks(&lzw(2, &frame.indices)));
+    out
+}
+
+/// A GIF89a stream of a `width` x `height` screen: the Global Color Table, `extensions` as given, then `frames`.
+pub fn gif(
+    width: u16,
+    height: u16,
+    global_color_table: Option<Vec<[u8; 3]>>,
+    frames: &[Frame],
+    extensions: &[u8],
+) -> Vec<u8> {
+    let mut out = b"GIF89a".to_vec();
+    out.extend(width.to_le_bytes());
+    out.extend(height.to_le_bytes());
+    let mut packed_fields = 0x70;
+    if let Some(global_color_table) = &global_color_table {
+        packed_fields |= 0x80 | table_size_field(global_color_table.len());
+    }
+    out.extend([packed_fields, 0, 0]);
+    if let Some(global_color_table) = &global_color_table {
+        push_color_table(&mut out, global_color_table);
+    }
+    out.extend_from_slice(extensions);
+    for frame in frames {
+        if let Some((disposal_method, delay_time, transparent)) = frame.graphic_control {
+            out.extend(graphic_control_extension(
+                disposal_method,
+                delay_time,
+                transparent,
+            ));
+        }
+        out.extend(image(frame));
+    }
+    out.push(0x3B);
+    out
+}
+
+/// Black, white, red and green.
+pub fn palette() -> Vec<[u8; 3]> {
+    vec![[0, 0, 0], [255, 255, 255], [255, 0, 0], [0, 255, 0]]
+}
+
+/// Comment Extension holding `text` in a single sub-block.
+pub fn comment(text: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x21, 0xFE];
+    out.extend(sub_blocks(text));
+    out
+}
+
+/// NETSCAPE2.0 looping extension with `loop_count`.
+pub fn netscape_loop(loop_count: u16) -> Vec<u8> {
+    let mut out = vec![0x21, 0xFF, 11];
+    out.extend(b"NETSCAPE2.0");
+    out.extend([3, 1]);
+    out.extend(loop_count.to_le_bytes());
+    out.push(0);
+    out
+}
+
+/// Path of a fixture in `res/`.
+pub fn fixture(name: &str) -> String {
+    format!("{}/res/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Writes `data` to a file of the system temp dir unique to this process and `name`, for the path based APIs.
+pub fn temp_file(name: &str, data: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("gif-parser-{}-{name}", std::process::id()));
+    std::fs::write(&path, data).unwrap();
+    path
+}
+
+/// Plain Text Extension drawing `text` in a one cell grid at the screen origin.
+pub fn plain_text(text: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x21, 0x01, 12, 0, 0, 0, 0, 8, 0, 8, 0, 8, 8, 1, 0];
+    out.extend(sub_blocks(text));
+    out
+}
+
+/// An empty directory of the system temp dir unique to this process and `name`.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gif-parser-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
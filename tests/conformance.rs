@@ -0,0 +1,160 @@
+//! Byte level examples of the blocks described in the GIF89a specification (`docs/gif89a.txt`),
+//! each checked against the values the specification says they encode.
+//! The complete stream is the classic 10x10 sample image built from those blocks.
+
+use gif_parser::extension::{DisposalMethod, GraphicControlExtension};
+use gif_parser::gif::{Gif, GifSignature, GifVersion, GlobalColorMap, LogicalScreenDescriptor};
+use gif_parser::parser::{GifBlock, GifBuffer};
+
+/// Header: Signature "GIF" and Version "89a".
+const HEADER: [u8; 6] = *b"GIF89a";
+
+/// Logical Screen Descriptor of a 10x10 screen. The packed fields `0x91` are
+/// Global Color Table Flag 1, Color Resolution 1, Sort Flag 0, Size of Global Color Table 1 (4 colors).
+const LOGICAL_SCREEN_DESCRIPTOR: [u8; 7] = [0x0A, 0x00, 0x0A, 0x00, 0x91, 0x00, 0x00];
+
+/// Global Color Table of `3 x 2^(1+1)` bytes: white, red, blue, black.
+const GLOBAL_COLOR_TABLE: [u8; 12] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x00, 0x00, 0x00,
+];
+
+/// Graphic Control Extension without delay, disposal or transparency.
+const GRAPHIC_CONTROL_EXTENSION: [u8; 8] = [0x21, 0xF9, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00];
+
+/// Image Descriptor of a 10x10 image at the screen origin, without Local Color Table or interlacing.
+const IMAGE_DESCRIPTOR: [u8; 10] = [0x2C, 0x00, 0x00, 0x00, 0x00, 0x0A, 0x00, 0x0A, 0x00, 0x00];
+
+/// Table Based Image Data: LZW Minimum Code Size 2 and a single data sub-block of 22 bytes.
+const IMAGE_DATA: [u8; 25] = [
+    0x02, 0x16, 0x8C, 0x2D, 0x99, 0x87, 0x2A, 0x1C, 0xDC, 0x33, 0xA0, 0x02, 0x75, 0xEC, 0x95, 0xFA,
+    0xA8, 0xDE, 0x60, 0x8C, 0x04, 0x91, 0x4C, 0x01, 0x00,
+];
+
+const TRAILER: u8 = 0x3B;
+
+/// The color indices `IMAGE_DATA` decompresses to, row by row.
+const INDICES: [[u8; 10]; 10] = [
+    [1, 1, 1, 1, 1, 2, 2, 2, 2, 2],
+    [1, 1, 1, 1, 1, 2, 2, 2, 2, 2],
+    [1, 1, 1, 1, 1, 2, 2, 2, 2, 2],
+    [1, 1, 1, 0, 0, 0, 0, 2, 2, 2],
+    [1, 1, 1, 0, 0, 0, 0, 2, 2, 2],
+    [2, 2, 2, 0, 0, 0, 0, 1, 1, 1],
+    [2, 2, 2, 0, 0, 0, 0, 1, 1, 1],
+    [2, 2, 2, 2, 2, 1, 1, 1, 1, 1],
+    [2, 2, 2, 2, 2, 1, 1, 1, 1, 1],
+    [2, 2, 2, 2, 2, 1, 1, 1, 1, 1],
+];
+
+fn data_stream() -> Vec<u8> {
+    [
+        &HEADER[..],
+        &LOGICAL_SCREEN_DESCRIPTOR,
+        &GLOBAL_COLOR_TABLE,
+        &GRAPHIC_CONTROL_EXTENSION,
+        &IMAGE_DESCRIPTOR,
+        &IMAGE_DATA,
+        &[TRAILER],
+    ]
+    .concat()
+}
+
+#[test]
+fn header() {
+    let signature = GifSignature::parse(&mut GifBuffer::from_bytes(&HEADER)).unwrap();
+    assert_eq!(signature.magic(), "GIF");
+
+    let gif = Gif::decode_bytes(&data_stream()).unwrap();
+    assert_eq!(gif.signature, signature);
+    assert_eq!(gif.version(), &GifVersion::GIF89a);
+
+    let gif87a = GifSignature::parse(&mut GifBuffer::from_bytes(b"GIF87a")).unwrap();
+    assert_ne!(gif87a, signature);
+}
+
+#[test]
+fn logical_screen_descriptor() {
+    let mut buf =
+        GifBuffer::from_bytes(&[&LOGICAL_SCREEN_DESCRIPTOR[..], &GLOBAL_COLOR_TABLE].concat());
+    let screen_descriptor = LogicalScreenDescriptor::parse(&mut buf).unwrap();
+    assert_eq!(screen_descriptor.logical_screen_width(), 10);
+    assert_eq!(screen_descriptor.logical_screen_height(), 10);
+    // a Pixel Aspect Ratio of 0 means no aspect ratio information is given
+    assert_eq!(screen_descriptor.pixel_aspect_ratio(), None);
+
+    let report = Gif::decode_bytes(&data_stream()).unwrap().palette_report();
+    assert!(report.global_color_table_present);
+    assert_eq!(report.global_color_table_size, 4);
+    assert!(!report.global_color_table_sorted);
+}
+
+#[test]
+fn pixel_aspect_ratio() {
+    // Aspect Ratio = (Pixel Aspect Ratio + 15) / 64: 49 is square, 1 and 255 are the tallest and widest pixels
+    for (field, ratio) in [(49, 1.0), (1, 0.25), (255, 4.21875)] {
+        let mut bytes = LOGICAL_SCREEN_DESCRIPTOR;
+        bytes[6] = field;
+        let screen_descriptor =
+            LogicalScreenDescriptor::parse(&mut GifBuffer::from_bytes(&bytes)).unwrap();
+        assert_eq!(screen_descriptor.pixel_aspect_ratio(), Some(ratio));
+    }
+}
+
+#[test]
+fn global_color_table() {
+    let mut buf =
+        GifBuffer::from_bytes(&[&LOGICAL_SCREEN_DESCRIPTOR[..], &GLOBAL_COLOR_TABLE].concat());
+    let screen_descriptor = LogicalScreenDescriptor::parse(&mut buf).unwrap();
+    let global_color_map = GlobalColorMap::parse(&mut buf, &screen_descriptor)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        global_color_map.as_rgb_triples(),
+        [
+            [0xFF, 0xFF, 0xFF],
+            [0xFF, 0x00, 0x00],
+            [0x00, 0x00, 0xFF],
+            [0x00, 0x00, 0x00]
+        ]
+    );
+    assert_eq!(buf.get_pointer(), buf.get_size());
+}
+
+#[test]
+fn graphic_control_extension() {
+    let mut buf = GifBuffer::from_bytes(&GRAPHIC_CONTROL_EXTENSION);
+    // Extension Introducer and Graphic Control Label
+    buf.skip_u8().unwrap();
+    buf.skip_u8().unwrap();
+    let graphic_control_extension = GraphicControlExtension::parse(&mut buf).unwrap();
+    assert_eq!(graphic_control_extension.delay_time(), 0);
+    assert_eq!(
+        graphic_control_extension.disposal_method(),
+        DisposalMethod::None
+    );
+    assert_eq!(graphic_control_extension.transparent_color_index(), None);
+    assert_eq!(buf.get_pointer(), buf.get_size());
+}
+
+#[test]
+fn minimal_image() {
+    let gif = Gif::decode_bytes(&data_stream()).unwrap();
+    assert_eq!(gif.descriptor_groups.len(), 1);
+    assert!(gif.terminator.is_some());
+
+    let group = &gif.descriptor_groups[0];
+    assert!(group.graphic_control_extension.is_some());
+    assert_eq!(group.image_descriptor.area(), 100);
+    assert!(!group.has_local_palette());
+    assert_eq!(group.raster_data.indices(), INDICES.concat());
+
+    let (width, height, rgb) = gif.first_frame_rgb().unwrap();
+    assert_eq!((width, height), (10, 10));
+    let colors = [[0xFF, 0xFF, 0xFF], [0xFF, 0x00, 0x00], [0x00, 0x00, 0xFF]];
+    let expected: Vec<u8> = INDICES
+        .concat()
+        .iter()
+        .flat_map(|&index| colors[index as usize])
+        .collect();
+    assert_eq!(rgb, expected);
+}
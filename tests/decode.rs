@@ -0,0 +1,1335 @@
+mod common;
+
+use gif_parser::error::GifError;
+use gif_parser::gif::{Gif, GifVersion};
+use gif_parser::options::{DecodeOptions, PaletteMode};
+use gif_parser::warning::GifWarning;
+use std::ops::ControlFlow;
+
+/// An unbounded search window covers the whole input instead of overflowing.
+#[test]
+fn scan_for_magic_without_bound() {
+    let mut data = vec![0xEF, 0xBB, 0xBF, b' '];
+    data.extend(common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![1])],
+        &[],
+    ));
+    let path = common::temp_file("scan-unbounded.gif", &data);
+    let options = DecodeOptions::new().scan_for_magic(usize::MAX);
+    let gif = Gif::decode_with_options(path.to_str().unwrap(), &options).unwrap();
+    assert_eq!(gif.descriptor_groups.len(), 1);
+}
+
+/// The magic is found in any case, like `GifSignature::parse` accepts a lowercase signature.
+#[test]
+fn scan_for_magic_ignores_case() {
+    let mut gif = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![1])],
+        &[],
+    );
+    gif[..3].copy_from_slice(b"gif");
+    let mut data = vec![0xEF, 0xBB, 0xBF];
+    data.extend(gif);
+    let path = common::temp_file("scan-lowercase.gif", &data);
+    let options = DecodeOptions::new().scan_for_magic(3);
+    let gif = Gif::decode_with_options(path.to_str().unwrap(), &options).unwrap();
+    assert_eq!(gif.descriptor_groups.len(), 1);
+}
+
+/// A Graphic Control Extension in front of a Plain Text Extension controls the text,
+/// not the image that follows it.
+#[test]
+fn plain_text_takes_its_graphic_control_extension() {
+    let mut extensions = common::graphic_control_extension(2, 50, Some(0));
+    extensions.extend(common::plain_text(b"hi"));
+    let data = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![1])],
+        &extensions,
+    );
+
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(gif.descriptor_groups.len(), 1);
+    assert_eq!(gif.descriptor_groups[0].graphic_control_extension, None);
+
+    let path = common::temp_file("plain-text.gif", &data);
+    let frame = Gif::decode_frame(path.to_str().unwrap(), 0).unwrap();
+    assert_eq!(frame.graphic_control_extension, None);
+}
+
+/// Decoded GIFs move between threads, so a batch of files can be decoded in parallel.
+#[test]
+fn decodes_fixtures_in_parallel() {
+    let paths = ["a.gif", "clown.gif", "stars.gif"].map(common::fixture);
+    let decoded: Vec<Gif> = std::thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .iter()
+            .map(|path| scope.spawn(move || Gif::decode(path).unwrap()))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    for (path, gif) in paths.iter().zip(&decoded) {
+        assert_eq!(gif, &Gif::decode(path).unwrap());
+    }
+}
+
+/// LZW Minimum Code Sizes outside 2..=8 are rejected before any decompression.
+#[test]
+fn lzw_code_size_out_of_range() {
+    let mut data = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![1])],
+        &[],
+    );
+    // Header, Logical Screen Descriptor, four color Global Color Table and Image Descriptor
+    let offset = 13 + 12 + 10;
+    assert_eq!(data[offset], 2);
+    for size in [0, 1, 9, 12] {
+        data[offset] = size;
+        assert!(matches!(
+            Gif::decode_bytes(&data),
+            Err(GifError::InvalidLzwCodeSize { size: found, offset: at }) if found == size && at == offset
+        ));
+    }
+}
+
+/// Running out of data, mid-frame or between blocks, is an `UnexpectedEof` at the end of the input.
+#[test]
+fn truncated_stream_is_unexpected_eof() {
+    let data = common::gif(
+        2,
+        2,
+        Some(common::palette()),
+        &[
+            common::frame(0, 0, 2, 2, vec![0, 1, 2, 3]),
+            common::frame(0, 0, 2, 2, vec![3, 2, 1, 0]),
+        ],
+        &[],
+    );
+    // inside the second image, before the Trailer, and right after the Global Color Table
+    for len in [data.len() - 5, data.len() - 1, 13 + 12] {
+        let truncated = &data[..len];
+        assert!(
+            matches!(
+                Gif::decode_bytes(truncated),
+                Err(GifError::UnexpectedEof { offset }) if offset == len
+            ),
+            "truncated to {len} bytes"
+        );
+    }
+}
+
+/// `decode_frame` returns the same frame a full decode does, Graphic Control Extension included.
+#[test]
+fn decode_frame_matches_full_decode() {
+    let frames: Vec<common::Frame> = (0..5u8)
+        .map(|index| common::Frame {
+            graphic_control: Some((1, 10 * index as u16, Some(index % 4))),
+            ..common::frame(index as u16, 0, 2, 2, vec![index % 4, 1, 2, 3])
+        })
+        .collect();
+    let data = common::gif(8, 2, Some(common::palette()), &frames, &[]);
+    let path = common::temp_file("five-frames.gif", &data);
+    let path = path.to_str().unwrap();
+
+    let gif = Gif::decode(path).unwrap();
+    assert_eq!(
+        Gif::decode_frame(path, 2).unwrap(),
+        gif.descriptor_groups[2]
+    );
+    assert_eq!(
+        Gif::decode_frame(path, 4).unwrap(),
+        gif.descriptor_groups[4]
+    );
+    assert!(matches!(
+        Gif::decode_frame(path, 5),
+        Err(GifError::FrameNotFound {
+            index: 5,
+            frame_count: 5
+        })
+    ));
+}
+
+/// Failing to open the file surfaces the `io::Error` itself.
+#[test]
+fn missing_file_is_io_error() {
+    let path = std::env::temp_dir().join("gif-parser-does-not-exist.gif");
+    match Gif::decode(path.to_str().unwrap()) {
+        Err(GifError::Io(err)) => assert_eq!(err.kind(), std::io::ErrorKind::NotFound),
+        other => panic!("expected an io error, got {other:?}"),
+    }
+}
+
+/// Options built fluently reach the decoder: RGB resolution and a version restriction.
+#[test]
+fn options_builder() {
+    assert_eq!(DecodeOptions::new(), DecodeOptions::default());
+    let options = DecodeOptions::new()
+        .strict(false)
+        .palette_mode(PaletteMode::Rgb)
+        .version(GifVersion::GIF89a);
+    assert!(!options.is_strict());
+    assert_eq!(options.get_palette_mode(), PaletteMode::Rgb);
+    assert_eq!(options.get_version(), Some(GifVersion::GIF89a));
+
+    let data = common::gif(
+        2,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 2, 1, vec![1, 2])],
+        &[],
+    );
+    let path = common::temp_file("options-builder.gif", &data);
+    let path = path.to_str().unwrap();
+    let gif = Gif::decode_with_options(path, &options).unwrap();
+    assert_eq!(
+        gif.descriptor_groups[0].raster_data.rgb(),
+        Some(&[255, 255, 255, 255, 0, 0][..])
+    );
+    assert_eq!(
+        Gif::decode(path).unwrap().descriptor_groups[0]
+            .raster_data
+            .rgb(),
+        None
+    );
+
+    let gif87a_only = DecodeOptions::new().version(GifVersion::GIF87a);
+    assert!(matches!(
+        Gif::decode_with_options(path, &gif87a_only),
+        Err(GifError::UnsupportedVersion(GifVersion::GIF89a))
+    ));
+}
+
+/// A frame without width or height is rejected at its Image Descriptor.
+#[test]
+fn zero_sized_frame() {
+    for (width, height) in [(0, 1), (1, 0)] {
+        let data = common::gif(
+            1,
+            1,
+            Some(common::palette()),
+            &[
+                common::frame(0, 0, 1, 1, vec![1]),
+                common::frame(0, 0, width, height, vec![]),
+            ],
+            &[],
+        );
+        // Header, Logical Screen Descriptor and Global Color Table, then the first frame
+        let offset = 13 + 12 + common::image(&common::frame(0, 0, 1, 1, vec![1])).len();
+        assert!(
+            matches!(
+                Gif::decode_bytes(&data),
+                Err(GifError::ZeroSizedFrame { offset: at }) if at == offset
+            ),
+            "{width}x{height}"
+        );
+    }
+}
+
+/// `validate` collects every violation of a stream instead of stopping at the first one.
+#[test]
+fn validate_collects_every_violation() {
+    // Reserved bits set in the Graphic Control Extension of the first frame
+    let mut extensions = common::graphic_control_extension(0, 0, None);
+    extensions[3] |= 0xE0;
+    let frames = [
+        common::frame(1, 0, 2, 1, vec![0, 1]),
+        common::frame(0, 0, 1, 1, vec![3]),
+    ];
+    let mut data = common::gif(2, 1, Some(vec![[0; 3], [255; 3]]), &frames, &extensions);
+    data[11] = 5; // Background Color Index past the two color table
+    data.pop(); // Trailer
+    let path = common::temp_file("validate.gif", &data);
+    let options = DecodeOptions::new().lenient_truncation(true);
+    let gif = Gif::decode_with_options(path.to_str().unwrap(), &options).unwrap();
+
+    let errors = gif.validate().unwrap_err();
+    assert_eq!(errors.len(), 5, "{errors:?}");
+    assert!(matches!(errors[0], GifError::BackgroundColorOutOfRange(5)));
+    assert!(matches!(errors[1], GifError::FrameOutOfBounds { frame: 0 }));
+    assert!(matches!(errors[2], GifError::ReservedBitsSet { frame: 0 }));
+    assert!(matches!(
+        errors[3],
+        GifError::ColorIndexOutOfRange { frame: 1, index: 3 }
+    ));
+    assert!(matches!(errors[4], GifError::MissingTrailer));
+
+    let fixture = Gif::decode(&common::fixture("stars.gif")).unwrap();
+    assert!(fixture.validate().is_ok());
+}
+
+/// Image data longer than two full sub-blocks decodes to every pixel.
+#[test]
+fn image_data_across_full_sub_blocks() {
+    let indices: Vec<u8> = (0..40 * 25).map(|pixel| (pixel % 7 % 4) as u8).collect();
+    assert!(common::lzw(2, &indices).len() > 2 * 255);
+    let data = common::gif(
+        40,
+        25,
+        Some(common::palette()),
+        &[common::frame(0, 0, 40, 25, indices.clone())],
+        &[],
+    );
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(gif.descriptor_groups[0].raster_data.indices(), indices);
+}
+
+/// One corrupt file in a directory fails on its own; files without the `.gif` extension are left out.
+#[test]
+fn decode_all_in_dir_keeps_going() {
+    let dir = common::temp_dir("decode-all");
+    std::fs::copy(common::fixture("stars.gif"), dir.join("a_valid.gif")).unwrap();
+    std::fs::write(dir.join("b_corrupt.gif"), b"GIF89a\x01\x00").unwrap();
+    std::fs::write(dir.join("notes.txt"), b"not a gif").unwrap();
+
+    let results = Gif::decode_all_in_dir(dir.to_str().unwrap());
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, dir.join("a_valid.gif"));
+    assert!(results[0].1.is_ok());
+    assert_eq!(results[1].0, dir.join("b_corrupt.gif"));
+    assert!(matches!(
+        results[1].1,
+        Err(GifError::UnexpectedEof { offset: 8 })
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// The trace holds every code in stream order, from the first Clear Code to End of Information.
+#[cfg(feature = "debug-trace")]
+#[test]
+fn trace_starts_with_clear_code() {
+    use gif_parser::gif::{ImageDescriptor, RasterData};
+    use gif_parser::parser::{GifBlock, GifBuffer};
+
+    let data = common::image(&common::frame(0, 0, 3, 1, vec![1, 2, 3]));
+    let mut buf = GifBuffer::from_bytes(&data);
+    ImageDescriptor::parse(&mut buf).unwrap();
+    let (indices, codes) = RasterData::decode_with_trace(&mut buf).unwrap();
+    assert_eq!(indices, [1, 2, 3]);
+    // Clear Code 4 in front of every two indices, End of Information 5
+    assert_eq!(codes, [4, 1, 2, 4, 3, 5]);
+
+    let mut buf = GifBuffer::read(&common::fixture("stars.gif")).unwrap();
+    let gif = Gif::decode(&common::fixture("stars.gif")).unwrap();
+    let image_offset = 13
+        + gif
+            .global_color_map
+            .as_ref()
+            .unwrap()
+            .as_rgb_triples()
+            .len()
+            * 3;
+    buf.read_slice_ref(image_offset).unwrap();
+    ImageDescriptor::parse(&mut buf).unwrap();
+    let (indices, codes) = RasterData::decode_with_trace(&mut buf).unwrap();
+    // stream order, so only the count matches for an interlaced image
+    assert_eq!(
+        indices.len(),
+        gif.descriptor_groups[0].raster_data.indices().len()
+    );
+    assert_eq!(codes.first(), Some(&256));
+    assert_eq!(codes.last(), Some(&257));
+}
+
+/// A Header, Logical Screen Descriptor and Global Color Table hidden in extension data are skipped as data,
+/// never parsed as a second Global Color Table.
+#[test]
+fn second_global_color_table_is_data() {
+    let global_color_table = vec![[255, 0, 0], [0, 255, 0], [0, 0, 255], [9, 9, 9]];
+    let other_table = vec![[1, 1, 1], [2, 2, 2], [3, 3, 3], [4, 4, 4]];
+    let mut lookalike = common::gif(2, 2, Some(other_table), &[], &[]);
+    lookalike.pop(); // Trailer
+
+    let mut extensions = common::comment(&lookalike);
+    // an unknown extension label carrying the same bytes
+    extensions.extend([0x21, 0xFA]);
+    extensions.extend(common::sub_blocks(&lookalike));
+    let frames = [
+        common::frame(0, 0, 2, 2, vec![0, 1, 2, 3]),
+        common::frame(0, 0, 2, 2, vec![3, 2, 1, 0]),
+    ];
+    let data = common::gif(2, 2, Some(global_color_table.clone()), &frames, &extensions);
+
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(gif.descriptor_groups.len(), 2);
+    assert_eq!(
+        gif.global_color_map.as_ref().unwrap().as_rgb_triples(),
+        global_color_table
+    );
+    assert_eq!(gif.comment_extensions[0].comment_data(), lookalike);
+}
+
+/// A stream cut off in its second frame keeps the first frame in lenient mode and fails by default.
+#[test]
+fn lenient_truncation_keeps_complete_frames() {
+    let frames: Vec<common::Frame> = (0..3)
+        .map(|_| common::frame(0, 0, 2, 2, vec![0, 1, 2, 3]))
+        .collect();
+    let data = common::gif(2, 2, Some(common::palette()), &frames, &[]);
+    // Header, Logical Screen Descriptor, Global Color Table, the first frame and part of the second
+    let cut = 13 + 12 + common::image(&frames[0]).len() + 14;
+    let path = common::temp_file("lenient-truncation.gif", &data[..cut]);
+    let path = path.to_str().unwrap();
+
+    assert!(matches!(
+        Gif::decode(path),
+        Err(GifError::UnexpectedEof { .. })
+    ));
+
+    let lenient = DecodeOptions::new().lenient_truncation(true);
+    let gif = Gif::decode_with_options(path, &lenient).unwrap();
+    assert_eq!(gif.descriptor_groups.len(), 1);
+    assert!(gif.terminator.is_none());
+    assert_eq!(gif.warnings, [GifWarning::Truncated { frame_count: 1 }]);
+
+    let complete = common::temp_file("lenient-complete.gif", &data);
+    let gif = Gif::decode_with_options(complete.to_str().unwrap(), &lenient).unwrap();
+    assert_eq!(gif.descriptor_groups.len(), 3);
+    assert!(gif.warnings.is_empty());
+}
+
+/// The footprint is the color table and the decoded indices; resolving RGB adds three bytes per pixel
+/// and keeping the image data adds its bytes.
+#[test]
+fn memory_footprint_of_a_fixture() {
+    let path = common::fixture("stars.gif");
+    let gif = Gif::decode(&path).unwrap();
+    let pixels = gif.estimate_decoded_size();
+    assert_eq!(pixels, 200 * 178);
+
+    let footprint = gif.memory_footprint();
+    assert_eq!(footprint, 256 * 3 + pixels);
+
+    let rgb = DecodeOptions::new().palette_mode(PaletteMode::Rgb);
+    let gif = Gif::decode_with_options(&path, &rgb).unwrap();
+    assert_eq!(gif.memory_footprint(), footprint + pixels * 3);
+
+    let kept = DecodeOptions::new().keep_image_data(true);
+    let gif = Gif::decode_with_options(&path, &kept).unwrap();
+    let image_data: usize = (0..gif.descriptor_groups.len())
+        .map(|index| gif.frame_bytes(index).unwrap().len())
+        .sum();
+    assert_eq!(gif.memory_footprint(), footprint + image_data);
+}
+
+/// A 2x2 frame whose data expands to 10000 indices stops at its pixel count, with or without a limit;
+/// a larger frame stops at `max_decoded_bytes`, strict or not, and a limit of exactly the pixel count still decodes.
+#[test]
+fn decode_limit_stops_bombs() {
+    let bomb = |width, height| {
+        common::gif(
+            width,
+            height,
+            Some(common::palette()),
+            &[common::frame(0, 0, width, height, vec![1; 10000])],
+            &[],
+        )
+    };
+    let path = common::temp_file("small-bomb.gif", &bomb(2, 2));
+    let path = path.to_str().unwrap();
+    for options in [
+        DecodeOptions::new(),
+        DecodeOptions::new().max_decoded_bytes(1000),
+    ] {
+        let gif = Gif::decode_with_options(path, &options).unwrap();
+        assert_eq!(gif.descriptor_groups[0].raster_data.indices(), [1; 4]);
+    }
+
+    let data = bomb(100, 100);
+    let path = common::temp_file("bomb.gif", &data);
+    let path = path.to_str().unwrap();
+    for strict in [true, false] {
+        let options = DecodeOptions::new().strict(strict).max_decoded_bytes(1000);
+        // reported inside the image data, past the Image Descriptor and the LZW Minimum Code Size
+        assert!(matches!(
+            Gif::decode_with_options(path, &options),
+            Err(GifError::DecodeLimitExceeded { limit: 1000, offset }) if (36..data.len()).contains(&offset)
+        ));
+    }
+
+    let path = common::fixture("stars.gif");
+    let exact = DecodeOptions::new().max_decoded_bytes(200 * 178);
+    assert!(Gif::decode_with_options(&path, &exact).is_ok());
+    let below = DecodeOptions::new().max_decoded_bytes(200 * 178 - 1);
+    assert!(matches!(
+        Gif::decode_with_options(&path, &below),
+        Err(GifError::DecodeLimitExceeded { .. })
+    ));
+}
+
+/// The callback sees every frame of a fixture in order, and `Break` stops after the frames seen so far.
+#[test]
+fn decode_streaming_calls_back_per_frame() {
+    let path = common::fixture("a.gif");
+    let gif = Gif::decode(&path).unwrap();
+    assert!(gif.descriptor_groups.len() > 3);
+
+    let mut frames = 0;
+    Gif::decode_streaming(&path, |group| {
+        assert_eq!(group, &gif.descriptor_groups[frames]);
+        frames += 1;
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    assert_eq!(frames, gif.descriptor_groups.len());
+
+    let mut frames = 0;
+    Gif::decode_streaming(&path, |_| {
+        frames += 1;
+        if frames == 3 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .unwrap();
+    assert_eq!(frames, 3);
+}
+
+/// Streaming applies the same options as a full decode: `max_frames` stops after the allowed frames were handed over,
+/// and `max_decoded_bytes` and a set cancel flag fail before the callback sees a frame.
+#[test]
+fn decode_streaming_with_options_applies_limits() {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    let frames: Vec<common::Frame> = (0..3)
+        .map(|index| common::frame(0, 0, 2, 1, vec![index, 1]))
+        .collect();
+    let data = common::gif(2, 1, Some(common::palette()), &frames, &[]);
+    let path = common::temp_file("streaming-options.gif", &data);
+    let path = path.to_str().unwrap();
+
+    let stream = |options: &DecodeOptions| {
+        let mut seen = 0;
+        let result = Gif::decode_streaming_with_options(path, options, |_| {
+            seen += 1;
+            ControlFlow::Continue(())
+        });
+        (result, seen)
+    };
+    assert!(matches!(stream(&DecodeOptions::new()), (Ok(()), 3)));
+    assert!(matches!(
+        stream(&DecodeOptions::new().max_frames(2)),
+        (
+            Err(GifError::LimitExceeded {
+                limit: "max_frames",
+                ..
+            }),
+            2
+        )
+    ));
+    assert!(matches!(
+        stream(&DecodeOptions::new().max_decoded_bytes(1)),
+        (Err(GifError::DecodeLimitExceeded { limit: 1, .. }), 0)
+    ));
+    let cancelled = DecodeOptions::new().cancel_flag(Arc::new(AtomicBool::new(true)));
+    assert!(matches!(stream(&cancelled), (Err(GifError::Cancelled), 0)));
+}
+
+/// A declared 256 color Global Color Table with only 10 bytes left is a `TruncatedColorTable`.
+#[test]
+fn truncated_global_color_table() {
+    let mut data = b"GIF89a".to_vec();
+    data.extend([2, 0, 2, 0, 0xF7, 0, 0]);
+    data.extend([7; 10]);
+    assert!(matches!(
+        Gif::decode_bytes(&data),
+        Err(GifError::TruncatedColorTable {
+            declared: 768,
+            available: 10,
+            offset: 13
+        })
+    ));
+}
+
+/// Decoding the same bytes twice gives equal values: no buffer position or other incidental state
+/// takes part in the comparison, whether read from a file or from memory.
+#[test]
+fn decoding_twice_is_equal() {
+    for name in ["a.gif", "clown.gif", "stars.gif"] {
+        let path = common::fixture(name);
+        let gif = Gif::decode(&path).unwrap();
+        assert_eq!(gif, Gif::decode(&path).unwrap(), "{name}");
+        assert_eq!(
+            gif,
+            Gif::decode_bytes(&std::fs::read(&path).unwrap()).unwrap(),
+            "{name}"
+        );
+    }
+
+    // the same frame with a Local Color Table, at two different offsets of the stream
+    let frame = || common::Frame {
+        local_color_table: Some(common::palette()),
+        ..common::frame(0, 0, 1, 1, vec![2])
+    };
+    let plain = Gif::decode_bytes(&common::gif(1, 1, None, &[frame()], &[])).unwrap();
+    let shifted = Gif::decode_bytes(&common::gif(
+        1,
+        1,
+        None,
+        &[frame()],
+        &common::comment(b"shift"),
+    ))
+    .unwrap();
+    assert_eq!(plain.descriptor_groups, shifted.descriptor_groups);
+}
+
+/// The `io::Error` of a missing file and the UTF-8 error behind a conversion are chained as sources.
+#[test]
+fn error_sources_chain() {
+    use std::error::Error;
+
+    let path = std::env::temp_dir().join("gif-parser-does-not-exist.gif");
+    let err = Gif::decode(path.to_str().unwrap()).unwrap_err();
+    let source = err.source().unwrap();
+    assert_eq!(
+        source.downcast_ref::<std::io::Error>().unwrap().kind(),
+        std::io::ErrorKind::NotFound
+    );
+
+    let err = GifError::from(String::from_utf8(vec![0xFF]).unwrap_err());
+    assert!(err
+        .source()
+        .unwrap()
+        .downcast_ref::<std::string::FromUtf8Error>()
+        .is_some());
+
+    let err = Gif::decode_bytes(b"\x89PNG\r\n\x1a\n").unwrap_err();
+    assert!(err
+        .source()
+        .unwrap()
+        .downcast_ref::<std::string::FromUtf8Error>()
+        .is_some());
+
+    assert!(GifError::ZeroSizedFrame { offset: 0 }.source().is_none());
+}
+
+/// `decode_bytes` decodes built streams like files do, and reports empty or foreign input as errors.
+#[test]
+fn decode_bytes_in_memory() {
+    let data = common::gif(
+        2,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 2, 1, vec![2, 3])],
+        &[],
+    );
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(gif.descriptor_groups[0].raster_data.indices(), [2, 3]);
+    let path = common::temp_file("decode-bytes.gif", &data);
+    assert_eq!(gif, Gif::decode(path.to_str().unwrap()).unwrap());
+
+    assert!(matches!(
+        Gif::decode_bytes(&[]),
+        Err(GifError::UnexpectedEof { offset: 0 })
+    ));
+    assert!(matches!(
+        Gif::decode_bytes(b"\x89PNG\r\n\x1a\n"),
+        Err(GifError::Utf8(_))
+    ));
+    assert!(matches!(
+        Gif::decode_bytes(b"PNG89a"),
+        Err(GifError::InvalidSignature { offset: 0, .. })
+    ));
+}
+
+/// The Pixel Aspect Ratio of a decoded stream: 49 is square, 0 carries no information.
+#[test]
+fn pixel_aspect_ratio_of_a_stream() {
+    let mut data = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![0])],
+        &[],
+    );
+    assert_eq!(data[12], 0);
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(gif.logical_screen_descriptor.pixel_aspect_ratio(), None);
+
+    data[12] = 49;
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(
+        gif.logical_screen_descriptor.pixel_aspect_ratio(),
+        Some(1.0)
+    );
+}
+
+/// Reserved bits, delays below the minimum and bytes after the Trailer are recorded as warnings, in stream order.
+#[test]
+fn warnings_are_collected() {
+    let delayed = common::Frame {
+        graphic_control: Some((0, 1, None)),
+        ..common::frame(0, 0, 1, 1, vec![0])
+    };
+    let mut data = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![0]), delayed],
+        &[],
+    );
+    // packed fields of the first Image Descriptor, after the Header, Logical Screen Descriptor and Global Color Table
+    assert_eq!(data[13 + 12], 0x2C);
+    data[13 + 12 + 9] |= 0b0001_1000;
+    data.extend([0, 0, 0]);
+    let path = common::temp_file("warnings.gif", &data);
+    let options = DecodeOptions::new().strict(false).min_delay(2);
+
+    let gif = Gif::decode_with_options(path.to_str().unwrap(), &options).unwrap();
+    assert_eq!(
+        gif.warnings,
+        [
+            GifWarning::ReservedBitsSet { frame: 0 },
+            GifWarning::DelayClamped { frame: 0, delay: 0 },
+            GifWarning::DelayClamped { frame: 1, delay: 1 },
+            GifWarning::TrailingBytes { count: 3 },
+        ]
+    );
+    assert_eq!(gif.warnings[0].to_string(), "frame 0 has reserved bits set");
+
+    for name in ["a.gif", "clown.gif", "stars.gif"] {
+        assert_eq!(Gif::decode(&common::fixture(name)).unwrap().warnings, []);
+    }
+}
+
+/// `probe` agrees with a full decode on every fixture, the 44 frame animation included.
+#[test]
+fn probe_matches_full_decode() {
+    for name in ["a.gif", "clown.gif", "stars.gif"] {
+        let path = common::fixture(name);
+        let probe = Gif::probe(&path).unwrap();
+        let gif = Gif::decode(&path).unwrap();
+        assert_eq!(probe.frame_count, gif.animation().frame_count(), "{name}");
+        assert_eq!(&probe.version, gif.version());
+        let screen_descriptor = &gif.logical_screen_descriptor;
+        assert_eq!(probe.width, screen_descriptor.logical_screen_width());
+        assert_eq!(probe.height, screen_descriptor.logical_screen_height());
+        assert_eq!(
+            probe.global_color_table_present,
+            gif.global_color_map.is_some()
+        );
+        assert_eq!(probe.decoded_size, gif.estimate_decoded_size());
+    }
+    assert_eq!(
+        Gif::probe(&common::fixture("a.gif")).unwrap().frame_count,
+        44
+    );
+}
+
+/// Image data that ends without an End of Information code decodes completely at the Block Terminator,
+/// also when the padding bits of its last byte could be read as one more code.
+#[test]
+fn image_data_without_end_of_information() {
+    // Clear Code, then the indices 1, 2, 3, 0 and no End of Information, in one sub-block
+    let image_data = [2, 2, 0x8C, 0x06, 0];
+    for (width, indices) in [(4, &[1, 2, 3, 0][..]), (3, &[1, 2, 3])] {
+        let mut data = common::gif(width, 1, Some(common::palette()), &[], &[]);
+        data.pop();
+        data.extend([0x2C, 0, 0, 0, 0, width as u8, 0, 1, 0, 0]);
+        data.extend(image_data);
+        data.push(0x3B);
+        let gif = Gif::decode_bytes(&data).unwrap();
+        assert_eq!(gif.descriptor_groups[0].raster_data.indices(), indices);
+    }
+
+    // the same indices with an explicit End of Information
+    let frames = [common::frame(0, 0, 4, 1, vec![1, 2, 3, 0])];
+    let gif = Gif::decode_bytes(&common::gif(4, 1, Some(common::palette()), &frames, &[])).unwrap();
+    assert_eq!(gif.descriptor_groups[0].raster_data.indices(), [1, 2, 3, 0]);
+}
+
+/// `stars.gif` is a GIF87a still, `a.gif` a GIF89a animation; only the latter supports animation.
+#[test]
+fn version_and_animation_support() {
+    let stars = Gif::decode(&common::fixture("stars.gif")).unwrap();
+    assert_eq!(stars.version(), &GifVersion::GIF87a);
+    assert!(!stars.supports_animation());
+
+    let animated = Gif::decode(&common::fixture("a.gif")).unwrap();
+    assert_eq!(animated.version(), &GifVersion::GIF89a);
+    assert!(animated.supports_animation());
+}
+
+/// Equal signatures, versions and screen descriptors collapse to one entry of a `HashSet`.
+#[test]
+fn parsed_types_as_set_keys() {
+    use std::collections::HashSet;
+
+    let a = Gif::decode(&common::fixture("a.gif")).unwrap();
+    let clown = Gif::decode(&common::fixture("clown.gif")).unwrap();
+    let stars = Gif::decode(&common::fixture("stars.gif")).unwrap();
+    assert_eq!(a.signature, clown.signature);
+
+    let signatures: HashSet<_> = [&a.signature, &clown.signature].into_iter().collect();
+    assert_eq!(signatures.len(), 1);
+    let versions: HashSet<_> = [a.version(), clown.version(), stars.version()]
+        .into_iter()
+        .collect();
+    assert_eq!(versions.len(), 2);
+
+    let again = Gif::decode(&common::fixture("a.gif")).unwrap();
+    let screens: HashSet<_> = [
+        &a.logical_screen_descriptor,
+        &again.logical_screen_descriptor,
+        &stars.logical_screen_descriptor,
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(screens.len(), 2);
+}
+
+/// The progress callback fires more often than there are frames, with growing positions that end at the total.
+#[test]
+fn progress_reports_during_decode() {
+    use std::sync::{Arc, Mutex};
+
+    let path = common::fixture("a.gif");
+    let total = std::fs::metadata(&path).unwrap().len() as usize;
+    let updates = Arc::new(Mutex::new(Vec::new()));
+    let recorder = Arc::clone(&updates);
+    let options = DecodeOptions::new().progress(move |consumed, total| {
+        recorder.lock().unwrap().push((consumed, total));
+    });
+
+    let gif = Gif::decode_with_options(&path, &options).unwrap();
+    let updates = updates.lock().unwrap();
+    assert!(updates.len() > gif.descriptor_groups.len());
+    assert!(updates.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert!(updates
+        .iter()
+        .all(|&(_, update_total)| update_total == total));
+    assert_eq!(updates.last(), Some(&(total, total)));
+}
+
+/// Setting the cancel flag once the first frame is read stops the decode before the second frame.
+#[test]
+fn cancel_flag_stops_between_frames() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    let frames: Vec<common::Frame> = (0..3)
+        .map(|index| common::frame(0, 0, 1, 1, vec![index]))
+        .collect();
+    let data = common::gif(1, 1, Some(common::palette()), &frames, &[]);
+    let path = common::temp_file("cancel.gif", &data);
+    let path = path.to_str().unwrap();
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let updates = Arc::new(Mutex::new(Vec::new()));
+    let (flag, recorder) = (Arc::clone(&cancel), Arc::clone(&updates));
+    // the first report comes right after the first frame, the only block in front of it
+    let options = DecodeOptions::new()
+        .cancel_flag(Arc::clone(&cancel))
+        .progress(move |consumed, _| {
+            recorder.lock().unwrap().push(consumed);
+            flag.store(true, Ordering::Relaxed);
+        });
+    assert!(matches!(
+        Gif::decode_with_options(path, &options),
+        Err(GifError::Cancelled)
+    ));
+    assert_eq!(updates.lock().unwrap().len(), 1);
+
+    cancel.store(false, Ordering::Relaxed);
+    let options = DecodeOptions::new().cancel_flag(cancel);
+    assert_eq!(
+        Gif::decode_with_options(path, &options)
+            .unwrap()
+            .descriptor_groups
+            .len(),
+        3
+    );
+}
+
+/// Each frame is read as Image Descriptor, Local Color Table when flagged, then LZW Minimum Code Size and
+/// image data sub-blocks, so after two frames the pointer sits exactly on the Trailer.
+#[test]
+fn frames_are_consumed_up_to_the_trailer() {
+    use gif_parser::gif::GifSignature;
+    use gif_parser::gif::{
+        GlobalColorMap, ImageDescriptor, LocalColorMap, LogicalScreenDescriptor, RasterData,
+    };
+    use gif_parser::parser::{GifBlock, GifBuffer};
+
+    let frames = [
+        common::frame(0, 0, 2, 2, vec![0, 1, 2, 3]),
+        common::Frame {
+            local_color_table: Some(vec![[1, 2, 3], [4, 5, 6]]),
+            ..common::frame(0, 0, 2, 1, vec![1, 0])
+        },
+    ];
+    let data = common::gif(2, 2, Some(common::palette()), &frames, &[]);
+    let mut buf = GifBuffer::from_bytes(&data);
+    GifSignature::parse(&mut buf).unwrap();
+    let screen_descriptor = LogicalScreenDescriptor::parse(&mut buf).unwrap();
+    GlobalColorMap::parse(&mut buf, &screen_descriptor).unwrap();
+
+    let options = DecodeOptions::default();
+    let mut end_of_frame = buf.get_pointer();
+    for frame in &frames {
+        let image_descriptor = ImageDescriptor::parse(&mut buf).unwrap();
+        let local_color_map = LocalColorMap::parse(&mut buf, &image_descriptor).unwrap();
+        assert_eq!(local_color_map.is_some(), frame.local_color_table.is_some());
+        let raster_data = RasterData::parse(&mut buf, &image_descriptor, &options).unwrap();
+        assert_eq!(raster_data.indices(), frame.indices);
+        end_of_frame += common::image(frame).len();
+        assert_eq!(buf.get_pointer(), end_of_frame);
+    }
+    assert_eq!(buf.get_pointer(), data.len() - 1);
+    assert_eq!(buf.read_u8().unwrap(), 0x3B);
+}
+
+/// Packs `(code, width)` pairs least significant bit first, the bit order of GIF's LZW data.
+fn pack_codes(codes: &[(u16, u32)]) -> Vec<u8> {
+    let (mut out, mut bits, mut bit_count) = (Vec::new(), 0u32, 0u32);
+    for &(code, width) in codes {
+        bits |= (code as u32) << bit_count;
+        bit_count += width;
+        while bit_count >= 8 {
+            out.push(bits as u8);
+            bits >>= 8;
+            bit_count -= 8;
+        }
+    }
+    if bit_count > 0 {
+        out.push(bits as u8);
+    }
+    out
+}
+
+/// An 11x1 frame of LZW Minimum Code Size 2 whose codes start 3 bits wide and grow to 4 once code 7 is assigned;
+/// a code one past the table (`KwKwK`) and a Clear Code back to 3 bits are in it as well.
+fn code_growth_gif() -> Vec<u8> {
+    let codes = [
+        (4, 3), // Clear Code
+        (1, 3),
+        (2, 3),  // assigns 6 = [1, 2]
+        (3, 3),  // assigns 7 = [2, 3], codes are 4 bits from here
+        (6, 4),  // [1, 2], assigns 8 = [3, 1]
+        (7, 4),  // [2, 3], assigns 9 = [1, 2, 2]
+        (10, 4), // not yet assigned: [2, 3] + 2
+        (4, 4),  // Clear Code, back to 3 bits
+        (0, 3),
+        (5, 3), // End of Information
+    ];
+    let mut image_data = vec![2];
+    image_data.extend(common::sub_blocks(&pack_codes(&codes)));
+
+    let mut data = common::gif(11, 1, Some(common::palette()), &[], &[]);
+    data.pop();
+    data.extend([0x2C, 0, 0, 0, 0, 11, 0, 1, 0, 0]);
+    data.extend(image_data);
+    data.push(0x3B);
+    data
+}
+
+/// With LZW Minimum Code Size 2 the codes start 3 bits wide and grow to 4 once code 7 is assigned;
+/// a code one past the table (`KwKwK`) and a Clear Code back to 3 bits decode as well.
+#[test]
+fn smallest_code_size_grows() {
+    let gif = Gif::decode_bytes(&code_growth_gif()).unwrap();
+    assert_eq!(
+        gif.descriptor_groups[0].raster_data.indices(),
+        [1, 2, 3, 1, 2, 2, 3, 2, 3, 2, 0]
+    );
+}
+
+/// Every frame decodes to the same indices as with the `gif` crate: the code growth stream above
+/// and the fixtures, which include interlaced and multi-frame images.
+#[test]
+fn indices_match_the_gif_crate() {
+    let mut streams = vec![code_growth_gif()];
+    for name in ["a.gif", "clown.gif", "stars.gif"] {
+        streams.push(std::fs::read(common::fixture(name)).unwrap());
+    }
+    for data in streams {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(&data[..]).unwrap();
+        let mut expected = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            expected.push(frame.buffer.to_vec());
+        }
+
+        let gif = Gif::decode_bytes(&data).unwrap();
+        let indices: Vec<Vec<u8>> = gif
+            .descriptor_groups
+            .iter()
+            .map(|group| group.raster_data.indices().to_vec())
+            .collect();
+        assert_eq!(indices, expected);
+    }
+}
+
+/// Image data bytes that equal the Image Separator or the Trailer are data: blocks are walked by
+/// their sub-block sizes, so the next frame and the Trailer are still found after them.
+#[test]
+fn introducer_bytes_inside_image_data() {
+    let codes = [
+        (4, 3), // Clear Code
+        (3, 3),
+        (1, 3),
+        (6, 3), // [3, 1], codes are 4 bits from here
+        (2, 4),
+        (6, 4),
+        (8, 4),  // [3, 1, 2]
+        (7, 4),  // [1, 3]
+        (11, 4), // [3, 1, 2, 1]
+        (11, 4),
+        (3, 4),
+        (5, 4), // End of Information
+    ];
+    let lzw_data = pack_codes(&codes);
+    assert!(lzw_data.contains(&0x2C) && lzw_data.contains(&0x3B));
+
+    let mut data = common::gif(21, 1, Some(common::palette()), &[], &[]);
+    data.pop();
+    data.extend([0x2C, 0, 0, 0, 0, 21, 0, 1, 0, 0, 2]);
+    data.extend(common::sub_blocks(&lzw_data));
+    data.extend(common::image(&common::frame(0, 0, 2, 1, vec![2, 0])));
+    data.push(0x3B);
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(gif.descriptor_groups.len(), 2);
+    assert_eq!(
+        gif.descriptor_groups[0].raster_data.indices(),
+        [3, 1, 3, 1, 2, 3, 1, 3, 1, 2, 1, 3, 3, 1, 2, 1, 3, 1, 2, 1, 3]
+    );
+    assert_eq!(gif.descriptor_groups[1].raster_data.indices(), [2, 0]);
+    assert!(gif.warnings.is_empty(), "{:?}", gif.warnings);
+}
+
+/// `max_frames`, `max_width` and `max_height` reject a stream as soon as it exceeds them,
+/// at the Image Descriptor or Logical Screen Descriptor that went past them.
+#[test]
+fn decode_limits() {
+    let frames: Vec<common::Frame> = (0..3)
+        .map(|_| common::frame(0, 0, 2, 3, vec![0, 1, 1, 0, 1, 0]))
+        .collect();
+    let data = common::gif(2, 3, Some(common::palette()), &frames, &[]);
+    let path = common::temp_file("limits.gif", &data);
+    let path = path.to_str().unwrap();
+    let decode = |options: DecodeOptions| Gif::decode_with_options(path, &options);
+    let second_frame = 25 + common::image(&frames[0]).len();
+
+    assert!(matches!(
+        decode(DecodeOptions::new().max_frames(1)),
+        Err(GifError::LimitExceeded {
+            limit: "max_frames",
+            value: 2,
+            max: 1,
+            offset
+        }) if offset == second_frame
+    ));
+    assert!(matches!(
+        decode(DecodeOptions::new().max_width(1)),
+        Err(GifError::LimitExceeded {
+            limit: "max_width",
+            value: 2,
+            max: 1,
+            offset
+        }) if offset == 6
+    ));
+    assert!(matches!(
+        decode(DecodeOptions::new().max_height(2)),
+        Err(GifError::LimitExceeded {
+            limit: "max_height",
+            value: 3,
+            max: 2,
+            offset
+        }) if offset == 6
+    ));
+    let at_the_limits = DecodeOptions::new()
+        .max_frames(3)
+        .max_width(2)
+        .max_height(3);
+    assert_eq!(decode(at_the_limits).unwrap().descriptor_groups.len(), 3);
+}
+
+/// A corrupted byte is reported at its own position in the input.
+#[test]
+fn errors_carry_the_corrupted_offset() {
+    let frame = common::Frame {
+        graphic_control: Some((0, 0, None)),
+        ..common::frame(0, 0, 1, 1, vec![1])
+    };
+    let data = common::gif(1, 1, Some(common::palette()), &[frame], &[]);
+    // Header, Logical Screen Descriptor and four color Global Color Table, then the Graphic Control Extension
+    let extension = 13 + 12;
+    let image_data = extension + 8 + 10 + 1;
+    assert_eq!(data[extension + 8], 0x2C);
+
+    let mut version = data.clone();
+    version[4] = b'3';
+    assert!(matches!(
+        Gif::decode_bytes(&version),
+        Err(GifError::UnknownVersion { offset: 3, .. })
+    ));
+
+    let mut block_size = data.clone();
+    block_size[extension + 2] = 5;
+    assert!(matches!(
+        Gif::decode_bytes(&block_size),
+        Err(GifError::BlockSizeMismatch { offset, .. }) if offset == extension + 2
+    ));
+
+    // the first 3 bit code of the raster becomes 7, past the end of the code table
+    let mut code = data.clone();
+    code[image_data + 1] = 0xFF;
+    assert!(matches!(
+        Gif::decode_bytes(&code),
+        Err(GifError::InvalidLzwCode { offset, .. }) if offset == image_data + 1
+    ));
+}
+
+/// A GIF behind 100 bytes of container data decodes from offset 100; offsets past the data are an `UnexpectedEof`.
+#[test]
+fn decode_bytes_at_an_offset() {
+    let gif = common::gif(
+        2,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 2, 1, vec![2, 3])],
+        &[],
+    );
+    let mut data: Vec<u8> = (0..100).collect();
+    data.extend(&gif);
+
+    assert_eq!(
+        Gif::decode_bytes_at(&data, 100).unwrap(),
+        Gif::decode_bytes(&gif).unwrap()
+    );
+    assert!(matches!(
+        Gif::decode_bytes_at(&data, 99),
+        Err(GifError::InvalidSignature { offset: 99, .. })
+    ));
+    assert!(matches!(
+        Gif::decode_bytes_at(&data, usize::MAX),
+        Err(GifError::UnexpectedEof { offset }) if offset == data.len()
+    ));
+}
+
+/// On a known animation the estimate is the summed `width * height` of the frames, which is also what the LZW data decodes to.
+#[test]
+fn estimate_decoded_size_of_an_animation() {
+    let path = common::fixture("a.gif");
+    let gif = Gif::decode(&path).unwrap();
+    let area: usize = gif
+        .descriptor_groups
+        .iter()
+        .map(|group| group.image_descriptor.area())
+        .sum();
+    let decoded: usize = gif
+        .descriptor_groups
+        .iter()
+        .map(|group| group.raster_data.indices().len())
+        .sum();
+
+    assert_eq!(gif.estimate_decoded_size(), area);
+    assert_eq!(decoded, area);
+    assert_eq!(Gif::probe(&path).unwrap().decoded_size, area);
+}
+
+/// Interlaced heights the four passes don't divide evenly, like 10, put every row in its place exactly once.
+#[test]
+fn interlace_with_uneven_heights() {
+    for height in 1u16..=17 {
+        // each row spells its own number in base 4, so a misplaced or repeated row shows up
+        let rows: Vec<u8> = (0..height)
+            .flat_map(|row| [(row % 4) as u8, (row / 4 % 4) as u8])
+            .collect();
+        let stream_order: Vec<u16> = [(0, 8), (4, 8), (2, 4), (1, 2)]
+            .into_iter()
+            .flat_map(|(start, step)| (start..height).step_by(step))
+            .collect();
+        let frame = common::Frame {
+            interlaced: true,
+            ..common::frame(
+                0,
+                0,
+                2,
+                height,
+                stream_order
+                    .iter()
+                    .flat_map(|&row| rows[row as usize * 2..row as usize * 2 + 2].to_vec())
+                    .collect(),
+            )
+        };
+        let data = common::gif(2, height, Some(common::palette()), &[frame], &[]);
+
+        let gif = Gif::decode_bytes(&data).unwrap();
+        assert_eq!(
+            gif.descriptor_groups[0].raster_data.indices(),
+            rows,
+            "height {height}"
+        );
+        let again = Gif::decode_bytes(&gif.encode()).unwrap();
+        assert_eq!(
+            again.descriptor_groups[0].raster_data.indices(),
+            rows,
+            "height {height}"
+        );
+    }
+}
+
+/// Indexing a directory only probes: a file whose image data is not valid LZW is still catalogued,
+/// while decoding the same directory fails on it.
+#[test]
+fn index_dir_skips_the_image_data() {
+    let dir = common::temp_dir("index-dir");
+    std::fs::copy(common::fixture("a.gif"), dir.join("a_animation.gif")).unwrap();
+    let mut bad_lzw = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![1])],
+        &[],
+    );
+    // Header, Logical Screen Descriptor, Global Color Table, Image Descriptor, LZW Minimum Code Size and block size
+    bad_lzw[13 + 12 + 10 + 2] = 0xFF;
+    std::fs::write(dir.join("b_bad_lzw.gif"), &bad_lzw).unwrap();
+    std::fs::write(dir.join("notes.txt"), b"not a gif").unwrap();
+
+    let index = Gif::index_dir(dir.to_str().unwrap());
+    assert_eq!(index.len(), 2);
+    assert_eq!(index[0].1.as_ref().unwrap().frame_count, 44);
+    assert_eq!(index[1].0, dir.join("b_bad_lzw.gif"));
+    assert_eq!(index[1].1.as_ref().unwrap().frame_count, 1);
+
+    let decoded = Gif::decode_all_in_dir(dir.to_str().unwrap());
+    assert!(matches!(decoded[1].1, Err(GifError::InvalidLzwCode { .. })));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// A file name that is not valid UTF-8 is opened as it is, not through a lossy copy of its name.
+#[cfg(unix)]
+#[test]
+fn dir_entries_with_non_utf8_names() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = common::temp_dir("non-utf8-names");
+    let path = dir.join(std::ffi::OsStr::from_bytes(b"caf\xE9.gif"));
+    std::fs::copy(common::fixture("stars.gif"), &path).unwrap();
+
+    let index = Gif::index_dir(dir.to_str().unwrap());
+    assert_eq!(index.len(), 1);
+    assert_eq!(index[0].0, path);
+    assert_eq!(index[0].1.as_ref().unwrap().frame_count, 1);
+    let decoded = Gif::decode_all_in_dir(dir.to_str().unwrap());
+    assert_eq!(
+        decoded[0].1.as_ref().unwrap(),
+        &Gif::decode(&common::fixture("stars.gif")).unwrap()
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Packed bytes with the sort flags and reserved bits set are kept as they were read, and encode back unchanged.
+#[test]
+fn raw_packed_fields_are_kept() {
+    let frame = common::Frame {
+        local_color_table: Some(vec![[1, 2, 3], [4, 5, 6]]),
+        interlaced: true,
+        ..common::frame(0, 0, 1, 1, vec![0])
+    };
+    let mut data = common::gif(1, 1, Some(common::palette()), &[frame], &[]);
+    // Global Color Table Flag, Color Resolution 8, Sort Flag and a 4 color table
+    data[10] = 0xF9;
+    // Header, Logical Screen Descriptor and Global Color Table, then the Image Descriptor's packed byte
+    let packed = 13 + 12 + 9;
+    assert_eq!(data[packed], 0xC0);
+    data[packed] |= 0x38;
+
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(gif.logical_screen_descriptor.packed_fields().raw(), 0xF9);
+    assert_eq!(
+        gif.descriptor_groups[0]
+            .image_descriptor
+            .packed_fields()
+            .raw(),
+        0xF8
+    );
+    let encoded = gif.encode();
+    assert_eq!(encoded[10], 0xF9);
+    assert_eq!(encoded[packed], 0xF8);
+}
+
+/// The raw image data of a frame is its LZW Minimum Code Size and sub-blocks exactly as in the stream.
+#[test]
+fn frame_bytes_are_the_stream_image_data() {
+    let frames = [
+        common::frame(0, 0, 2, 2, vec![0, 1, 2, 3]),
+        common::frame(1, 1, 1, 1, vec![3]),
+    ];
+    let data = common::gif(2, 2, Some(common::palette()), &frames, &[]);
+    let path = common::temp_file("frame-bytes.gif", &data);
+    let options = DecodeOptions::new().keep_image_data(true);
+    let gif = Gif::decode_with_options(path.to_str().unwrap(), &options).unwrap();
+
+    for (index, frame) in frames.iter().enumerate() {
+        let bytes = gif.frame_bytes(index).unwrap();
+        assert_eq!(bytes.first(), Some(&2));
+        assert_eq!(bytes.last(), Some(&0));
+        let mut expected = vec![2];
+        expected.extend(common::sub_blocks(&common::lzw(2, &frame.indices)));
+        assert_eq!(bytes, expected);
+    }
+    assert!(matches!(
+        gif.frame_bytes(2),
+        Err(GifError::FrameNotFound {
+            index: 2,
+            frame_count: 2
+        })
+    ));
+}
+
+/// After `recolor` the raw image data of a frame decodes to the new indices, not the ones read from the stream.
+#[test]
+fn frame_bytes_follow_recolor() {
+    let data = common::gif(
+        2,
+        2,
+        Some(common::palette()),
+        &[common::frame(0, 0, 2, 2, vec![0, 1, 1, 0])],
+        &[],
+    );
+    let path = common::temp_file("frame-bytes-recolor.gif", &data);
+    let options = DecodeOptions::new().keep_image_data(true);
+    let mut gif = Gif::decode_with_options(path.to_str().unwrap(), &options).unwrap();
+    let stream_bytes = gif.frame_bytes(0).unwrap();
+    let mut mapping: [u8; 256] = std::array::from_fn(|i| i as u8);
+    mapping.swap(0, 1);
+    gif.recolor(&mapping, false);
+
+    // the same stream with the image data swapped for the recolored frame's
+    let start = data.len() - 1 - stream_bytes.len();
+    assert_eq!(data[start..data.len() - 1], stream_bytes);
+    let mut recolored = data[..start].to_vec();
+    recolored.extend(gif.frame_bytes(0).unwrap());
+    recolored.push(0x3B);
+    let decoded = Gif::decode_bytes(&recolored).unwrap();
+    assert_eq!(
+        decoded.descriptor_groups[0].raster_data.indices(),
+        gif.descriptor_groups[0].raster_data.indices()
+    );
+    assert_eq!(
+        decoded.descriptor_groups[0].raster_data.indices(),
+        [1, 0, 0, 1]
+    );
+}
@@ -0,0 +1,199 @@
+mod common;
+
+use gif_parser::encoder::GifEncoder;
+use gif_parser::error::GifError;
+use gif_parser::gif::Gif;
+use std::io::Cursor;
+
+fn with_metadata() -> Vec<u8> {
+    let mut extensions = common::netscape_loop(0);
+    extensions.extend(common::comment(b"made by hand"));
+    extensions.extend(common::graphic_control_extension(0, 20, None));
+    extensions.extend(common::plain_text(b"hi"));
+    common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![1])],
+        &extensions,
+    )
+}
+
+/// Comment and Plain Text Extensions are kept while decoding and written again by `encode`,
+/// the plain text together with its Graphic Control Extension.
+#[test]
+fn encode_keeps_comments_and_plain_text() {
+    let gif = Gif::decode_bytes(&with_metadata()).unwrap();
+    assert_eq!(gif.comment_extensions.len(), 1);
+    assert_eq!(gif.comment_extensions[0].comment_data(), b"made by hand");
+    assert_eq!(gif.plain_text_extensions.len(), 1);
+    let plain_text = &gif.plain_text_extensions[0];
+    assert_eq!(plain_text.plain_text_data(), b"hi");
+    assert_eq!(plain_text.text_grid(), (0, 0, 8, 8));
+    assert_eq!(plain_text.character_cell(), (8, 8));
+    assert_eq!(
+        plain_text.graphic_control_extension().unwrap().delay_time(),
+        20
+    );
+
+    let round_trip = Gif::decode_bytes(&gif.encode()).unwrap();
+    assert_eq!(round_trip.comment_extensions, gif.comment_extensions);
+    assert_eq!(round_trip.plain_text_extensions, gif.plain_text_extensions);
+    assert_eq!(round_trip.descriptor_groups, gif.descriptor_groups);
+}
+
+/// Stripping drops the comment and the plain text but keeps the looping extension and the frames.
+#[test]
+fn strip_metadata_removes_comments_and_plain_text() {
+    let gif = Gif::decode_bytes(&with_metadata()).unwrap();
+    let stripped = gif.strip_metadata();
+    assert!(!stripped.windows(2).any(|bytes| bytes == [0x21, 0xFE]));
+    assert!(!stripped.windows(2).any(|bytes| bytes == [0x21, 0x01]));
+
+    let stripped = Gif::decode_bytes(&stripped).unwrap();
+    assert!(stripped.comment_extensions.is_empty());
+    assert!(stripped.plain_text_extensions.is_empty());
+    assert_eq!(stripped.loop_count(), Some(0));
+    assert_eq!(stripped.descriptor_groups, gif.descriptor_groups);
+}
+
+/// Frames written one by one into an in-memory `Cursor` decode back to the same GIF; a full writer is an `Io` error.
+#[test]
+fn encoder_into_a_cursor() {
+    let gif = Gif::decode(&common::fixture("clown.gif")).unwrap();
+    let mut encoder = GifEncoder::new(Cursor::new(Vec::new()));
+    encoder
+        .write_header(
+            &gif.signature,
+            &gif.logical_screen_descriptor,
+            gif.global_color_map.as_ref(),
+        )
+        .unwrap();
+    for group in &gif.descriptor_groups {
+        encoder.write_frame(group).unwrap();
+    }
+    let data = encoder.finish().unwrap().into_inner();
+
+    assert_eq!(
+        Gif::decode_bytes(&data).unwrap().rgba_frames().unwrap(),
+        gif.rgba_frames().unwrap()
+    );
+    assert_eq!(
+        gif.encode_to(Cursor::new(Vec::new())).unwrap().into_inner(),
+        gif.encode()
+    );
+
+    let mut full = [0; 4];
+    let mut encoder = GifEncoder::new(&mut full[..]);
+    assert!(matches!(
+        encoder.write_header(&gif.signature, &gif.logical_screen_descriptor, None),
+        Err(GifError::Io(_))
+    ));
+}
+
+/// Cropping a 4x4 GIF to its 2x2 center clips the frames reaching past it, moves them to the new origin,
+/// and every composited frame shows the same region of the original.
+#[test]
+fn crop_to_the_center() {
+    let frames = [
+        common::frame(0, 0, 4, 4, (0..16).map(|i| i % 4).collect()),
+        common::frame(2, 2, 2, 2, vec![3, 3, 3, 3]),
+    ];
+    let gif = Gif::decode_bytes(&common::gif(4, 4, Some(common::palette()), &frames, &[])).unwrap();
+
+    let cropped = Gif::decode_bytes(&gif.crop((1, 1, 2, 2)).unwrap()).unwrap();
+    assert_eq!(cropped.logical_screen_descriptor.logical_screen_width(), 2);
+    assert_eq!(cropped.logical_screen_descriptor.logical_screen_height(), 2);
+    assert_eq!(cropped.descriptor_groups[0].bounding_box(), (0, 0, 2, 2));
+    assert_eq!(
+        cropped.descriptor_groups[0].raster_data.indices(),
+        [1, 2, 1, 2]
+    );
+    assert_eq!(cropped.descriptor_groups[1].bounding_box(), (1, 1, 1, 1));
+
+    let original = gif.rgba_frames().unwrap();
+    for (frame, cropped) in original.iter().zip(cropped.rgba_frames().unwrap()) {
+        let region: Vec<u8> = (1..3)
+            .flat_map(|row| frame[(row * 4 + 1) * 4..(row * 4 + 3) * 4].to_vec())
+            .collect();
+        assert_eq!(cropped, region);
+    }
+
+    assert!(matches!(
+        gif.crop((3, 3, 2, 1)),
+        Err(GifError::CropOutOfBounds { .. })
+    ));
+}
+
+/// Extensions between images are written back into the same gap, so a plain text shown after the first frame
+/// still comes after it, and a comment behind the last image stays behind it.
+#[test]
+fn encode_keeps_extensions_between_frames() {
+    let mut data = common::gif(1, 1, Some(common::palette()), &[], &[]);
+    data.pop();
+    data.extend(common::comment(b"before"));
+    data.extend(common::image(&common::frame(0, 0, 1, 1, vec![0])));
+    data.extend(common::graphic_control_extension(0, 50, None));
+    data.extend(common::plain_text(b"between"));
+    data.extend(common::image(&common::frame(0, 0, 1, 1, vec![1])));
+    data.extend(common::comment(b"after"));
+    data.push(0x3B);
+
+    let gif = Gif::decode_bytes(&data).unwrap();
+    let comments: Vec<usize> = gif
+        .comment_extensions
+        .iter()
+        .map(|comment| comment.before_frame())
+        .collect();
+    assert_eq!(comments, [0, 2]);
+    assert_eq!(gif.plain_text_extensions[0].before_frame(), 1);
+
+    let encoded = gif.encode();
+    assert_eq!(Gif::decode_bytes(&encoded).unwrap(), gif);
+    let position = |bytes: &[u8]| {
+        encoded
+            .windows(bytes.len())
+            .position(|window| window == bytes)
+    };
+    let images: Vec<usize> = encoded
+        .iter()
+        .enumerate()
+        .filter(|&(_, &byte)| byte == 0x2C)
+        .map(|(offset, _)| offset)
+        .collect();
+    assert!(position(b"before").unwrap() < images[0]);
+    assert!((images[0]..images[1]).contains(&position(b"between").unwrap()));
+    assert!(position(b"after").unwrap() > images[1]);
+}
+
+/// A comment in front of a repeated frame keeps that frame from being merged away,
+/// and the comment after the last frame moves along with the frame count.
+#[test]
+fn optimize_keeps_extensions_in_place() {
+    let frame = |indices| common::Frame {
+        graphic_control: Some((0, 10, None)),
+        ..common::frame(0, 0, 1, 1, indices)
+    };
+    let mut data = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[frame(vec![1]), frame(vec![1])],
+        &[],
+    );
+    data.pop();
+    data.extend(common::comment(b"keep me"));
+    data.extend(common::graphic_control_extension(0, 10, None));
+    data.extend(common::image(&frame(vec![1])));
+    data.extend(common::comment(b"the end"));
+    data.push(0x3B);
+
+    let optimized = Gif::decode_bytes(&data).unwrap().optimize();
+    assert_eq!(optimized.descriptor_groups.len(), 2);
+    let comments: Vec<usize> = optimized
+        .comment_extensions
+        .iter()
+        .map(|comment| comment.before_frame())
+        .collect();
+    assert_eq!(comments, [1, 2]);
+}
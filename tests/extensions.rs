@@ -0,0 +1,152 @@
+mod common;
+
+use gif_parser::extension::{DisposalMethod, GraphicControlExtension};
+use gif_parser::gif::Gif;
+use gif_parser::parser::{GifBlock, GifBuffer};
+
+/// `XMP Data` Application Extension: the XML packet runs unsplit through the data,
+/// followed by the 258 byte magic trailer `0x01, 0xFF, 0xFE, .., 0x00, 0x00`.
+fn xmp_extension(xml: &str) -> Vec<u8> {
+    let mut out = vec![0x21, 0xFF, 11];
+    out.extend(b"XMP DataXMP");
+    out.extend(xml.as_bytes());
+    out.push(1);
+    out.extend((0..=255u8).rev());
+    out.push(0);
+    out
+}
+
+/// The XML of an embedded XMP packet is extracted whole, and the frames after it still decode.
+#[test]
+fn xmp_packet_is_extracted() {
+    let xml = "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\
+        <x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF/></x:xmpmeta><?xpacket end=\"w\"?>";
+    let frames = [
+        common::frame(0, 0, 1, 1, vec![0]),
+        common::frame(0, 0, 1, 1, vec![1]),
+    ];
+    let data = common::gif(1, 1, Some(common::palette()), &frames, &xmp_extension(xml));
+
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(gif.xmp().as_deref(), Some(xml));
+    assert_eq!(gif.descriptor_groups.len(), 2);
+    assert_eq!(
+        Gif::decode_bytes(&gif.encode()).unwrap().xmp().as_deref(),
+        Some(xml)
+    );
+
+    assert_eq!(
+        Gif::decode(&common::fixture("clown.gif")).unwrap().xmp(),
+        None
+    );
+}
+
+/// Every value of the 3 bit Disposal Method field parses into its `DisposalMethod` and encodes back unchanged.
+#[test]
+fn disposal_method_values() {
+    let expected = [
+        DisposalMethod::None,
+        DisposalMethod::DoNotDispose,
+        DisposalMethod::RestoreBackground,
+        DisposalMethod::RestorePrevious,
+        DisposalMethod::Reserved(4),
+        DisposalMethod::Reserved(5),
+        DisposalMethod::Reserved(6),
+        DisposalMethod::Reserved(7),
+    ];
+    for (value, disposal_method) in (0..8u8).zip(expected) {
+        let mut buf =
+            GifBuffer::from_bytes(&common::graphic_control_extension(value, 5, None)[2..]);
+        let graphic_control_extension = GraphicControlExtension::parse(&mut buf).unwrap();
+        assert_eq!(graphic_control_extension.disposal_method(), disposal_method);
+        assert_eq!(u8::from(disposal_method), value);
+    }
+}
+
+/// The sub-blocks of an `ICCRGBG1012` Application Extension are joined into the profile, across a re-encode.
+#[test]
+fn icc_profile_is_captured() {
+    let profile: Vec<u8> = (0..600u32).map(|i| (i * 7) as u8).collect();
+    let mut extension = vec![0x21, 0xFF, 11];
+    extension.extend(b"ICCRGBG1012");
+    extension.extend(common::sub_blocks(&profile));
+    let frames = [common::frame(0, 0, 1, 1, vec![0])];
+
+    let gif = Gif::decode_bytes(&common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &frames,
+        &extension,
+    ))
+    .unwrap();
+    assert_eq!(gif.icc_profile(), Some(profile.clone()));
+    assert_eq!(
+        Gif::decode_bytes(&gif.encode()).unwrap().icc_profile(),
+        Some(profile)
+    );
+
+    let plain =
+        Gif::decode_bytes(&common::gif(1, 1, Some(common::palette()), &frames, &[])).unwrap();
+    assert_eq!(plain.icc_profile(), None);
+}
+
+/// A Delay Time of 25 hundredths is a quarter second.
+#[test]
+fn delay_duration() {
+    let mut buf = GifBuffer::from_bytes(&common::graphic_control_extension(0, 25, None)[2..]);
+    let graphic_control_extension = GraphicControlExtension::parse(&mut buf).unwrap();
+    assert_eq!(graphic_control_extension.delay_time(), 25);
+    assert_eq!(
+        graphic_control_extension.delay_duration(),
+        std::time::Duration::from_millis(250)
+    );
+}
+
+/// A comment with a byte that is not UTF-8 still decodes, its text showing a replacement character.
+#[test]
+fn comment_with_invalid_utf8() {
+    let frames = [common::frame(0, 0, 1, 1, vec![0])];
+    let data = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &frames,
+        &common::comment(b"caf\xFF comment"),
+    );
+
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(gif.descriptor_groups.len(), 1);
+    let comment = &gif.comment_extensions[0];
+    assert_eq!(comment.comment_data(), b"caf\xFF comment");
+    assert_eq!(comment.text(), "caf\u{FFFD} comment");
+}
+
+/// A comment split over three sub-blocks reports all three and their payload size, not counting the size bytes.
+#[test]
+fn comment_sub_block_layout() {
+    let mut extension = vec![0x21, 0xFE];
+    for chunk in [&b"split "[..], b"over ", b"three"] {
+        extension.push(chunk.len() as u8);
+        extension.extend(chunk);
+    }
+    extension.push(0);
+    let frames = [common::frame(0, 0, 1, 1, vec![0])];
+    let data = common::gif(1, 1, Some(common::palette()), &frames, &extension);
+
+    let gif = Gif::decode_bytes(&data).unwrap();
+    let comment = &gif.comment_extensions[0];
+    assert_eq!(comment.comment_data(), b"split over three");
+    assert_eq!(comment.sub_block_count(), 3);
+    assert_eq!(comment.data_size(), 16);
+
+    let xml = "<x:xmpmeta/>";
+    let data = common::gif(1, 1, Some(common::palette()), &frames, &xmp_extension(xml));
+    let gif = Gif::decode_bytes(&data).unwrap();
+    let xmp = &gif.application_extensions[0];
+    // read as sub-blocks, every byte of the packet and the magic trailer is a size byte or a payload
+    assert_eq!(
+        xmp.sub_block_count() + xmp.data_size(),
+        xmp.application_data().len()
+    );
+}
@@ -0,0 +1,240 @@
+mod common;
+
+use common::{frame, gif, palette, Frame};
+use gif_parser::error::GifError;
+use gif_parser::gif::{Gif, PaletteReport};
+use gif_parser::palette::{self, Palette};
+use gif_parser::parser::GifBuffer;
+
+/// A four color screen with a plain frame and a frame carrying a two color Local Color Table.
+fn global_and_local() -> Gif {
+    let local = Frame {
+        local_color_table: Some(vec![[1, 2, 3], [4, 5, 6]]),
+        ..frame(0, 0, 1, 1, vec![1])
+    };
+    Gif::decode_bytes(&gif(
+        1,
+        1,
+        Some(palette()),
+        &[frame(0, 0, 1, 1, vec![0]), local],
+        &[],
+    ))
+    .unwrap()
+}
+
+/// The report counts the Global Color Table and every Local Color Table.
+#[test]
+fn palette_report() {
+    assert_eq!(
+        global_and_local().palette_report(),
+        PaletteReport {
+            global_color_table_present: true,
+            global_color_table_size: 4,
+            global_color_table_sorted: false,
+            local_color_table_count: 1,
+            any_frame_sorted: false,
+        }
+    );
+}
+
+/// The triples are the Global Color Table bytes of the file, three at a time.
+#[test]
+fn rgb_triples_match_the_table_bytes() {
+    let data = std::fs::read(common::fixture("stars.gif")).unwrap();
+    let gif = Gif::decode_bytes(&data).unwrap();
+    let global_color_map = gif.global_color_map.as_ref().unwrap();
+    let triples = global_color_map.as_rgb_triples();
+
+    let table = &data[13..13 + triples.len() * 3];
+    assert_eq!(triples.len(), gif.palette_report().global_color_table_size);
+    assert_eq!(triples.concat(), table);
+    assert!(triples
+        .iter()
+        .zip(table.chunks_exact(3))
+        .all(|(triple, bytes)| triple == bytes));
+}
+
+/// The PNG palette is the table's RGB bytes in color index order, nothing else.
+#[test]
+fn png_palette_is_the_table_bytes() {
+    let gif = global_and_local();
+    let global_color_map = gif.global_color_map.as_ref().unwrap();
+    assert_eq!(
+        global_color_map.to_png_palette(),
+        [0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 255, 0]
+    );
+}
+
+/// Queries map to the closest table color; exact matches and ties resolve to the lowest index.
+#[test]
+fn nearest_index() {
+    let table = vec![
+        [0, 0, 0],
+        [255, 0, 0],
+        [0, 255, 0],
+        [250, 250, 250],
+        [255, 0, 0],
+    ];
+    let gif =
+        Gif::decode_bytes(&gif(1, 1, Some(table), &[frame(0, 0, 1, 1, vec![0])], &[])).unwrap();
+    let global_color_map = gif.global_color_map.as_ref().unwrap();
+    assert_eq!(global_color_map.nearest_index((200, 30, 30)), 1);
+    assert_eq!(global_color_map.nearest_index((255, 0, 0)), 1);
+    assert_eq!(global_color_map.nearest_index((255, 255, 255)), 3);
+    assert_eq!(global_color_map.nearest_index((10, 10, 10)), 0);
+    assert_eq!(global_color_map.nearest_index((10, 200, 10)), 2);
+}
+
+/// `Palette::parse` reads three bytes per color, and lookups past the table are `None`.
+#[test]
+fn palette_parse_and_lookup() {
+    let mut buf = GifBuffer::from_bytes(&[1, 2, 3, 4, 5, 6, 7]);
+    let palette = Palette::parse(&mut buf, 2).unwrap();
+    assert_eq!(buf.get_pointer(), 6);
+    assert_eq!(palette.len(), 2);
+    assert_eq!(palette.entries(), [[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(palette.get(1), Some(&[4, 5, 6]));
+    assert_eq!(palette.get(2), None);
+    assert_eq!(palette.nearest_index((5, 5, 5)), 1);
+
+    assert!(matches!(
+        Palette::parse(&mut buf, 1),
+        Err(GifError::TruncatedColorTable {
+            declared: 3,
+            available: 1,
+            offset: 6
+        })
+    ));
+}
+
+/// The largest size field gives 256 colors; a table of more is rejected up front, even with the bytes available.
+#[test]
+fn color_table_size_is_capped() {
+    let colors: Vec<[u8; 3]> = (0..=255).map(|index| [index, 0, 0]).collect();
+    let data = gif(1, 1, Some(colors), &[frame(0, 0, 1, 1, vec![0])], &[]);
+    assert_eq!(data[10] & 0b111, 7);
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert_eq!(
+        gif.global_color_map.unwrap().palette().len(),
+        palette::MAX_COLORS
+    );
+
+    let bytes = vec![0; 512 * 3];
+    let mut buf = GifBuffer::from_bytes(&bytes);
+    assert!(matches!(
+        Palette::parse(&mut buf, 512),
+        Err(GifError::InvalidColorTableSize {
+            len: 512,
+            offset: 0
+        })
+    ));
+    assert_eq!(buf.get_pointer(), 0);
+}
+
+/// Global and Local Color Tables parse into the same `Palette`.
+#[test]
+fn global_and_local_tables_share_palette() {
+    let gif = global_and_local();
+    let global: &Palette = gif.global_color_map.as_ref().unwrap().palette();
+    let local: &Palette = gif.descriptor_groups[1]
+        .local_color_map
+        .as_ref()
+        .unwrap()
+        .palette();
+    assert_eq!(global.entries(), palette());
+    assert_eq!(local.entries(), [[1, 2, 3], [4, 5, 6]]);
+}
+
+/// A two color table lists both colors without an ellipsis; a 256 color table shows its ends around one.
+#[test]
+fn global_color_map_display() {
+    let table = vec![[1, 2, 3], [255, 0, 16]];
+    let gif =
+        Gif::decode_bytes(&gif(1, 1, Some(table), &[frame(0, 0, 1, 1, vec![0])], &[])).unwrap();
+    assert_eq!(
+        gif.global_color_map.as_ref().unwrap().to_string(),
+        "Global Color Map: \n  {size: 6}\n\n  000 => [0x01, 0x02, 0x03]\n  001 => [0xff, 0x00, 0x10]"
+    );
+
+    let gif = Gif::decode(&common::fixture("stars.gif")).unwrap();
+    let global_color_map = gif.global_color_map.as_ref().unwrap();
+    assert_eq!(global_color_map.iter().count(), 256);
+    let display = global_color_map.to_string();
+    assert_eq!(display.matches(" => ").count(), 6);
+    assert!(display.contains("  002 => [0x80, 0x00, 0x00]\n    ...\n  253 => "));
+    assert!(display.ends_with("  255 => [0xff, 0xff, 0xff]"));
+}
+
+/// Only the frame carrying a Local Color Table has a local palette.
+#[test]
+fn has_local_palette() {
+    let gif = global_and_local();
+    let local: Vec<bool> = gif
+        .descriptor_groups
+        .iter()
+        .map(|group| group.has_local_palette())
+        .collect();
+    assert_eq!(local, [false, true]);
+}
+
+/// The most frequent opaque color of the first frame wins; transparent pixels aren't counted.
+#[test]
+fn dominant_color() {
+    let indices = vec![2, 2, 1, 2, 3, 2, 1, 1, 0];
+    let opaque = gif(
+        3,
+        3,
+        Some(palette()),
+        &[frame(0, 0, 3, 3, indices.clone())],
+        &[],
+    );
+    assert_eq!(
+        Gif::decode_bytes(&opaque).unwrap().dominant_color(),
+        Some((255, 0, 0))
+    );
+
+    let transparent = Frame {
+        graphic_control: Some((0, 0, Some(2))),
+        ..frame(0, 0, 3, 3, indices)
+    };
+    let data = gif(3, 3, Some(palette()), &[transparent], &[]);
+    assert_eq!(
+        Gif::decode_bytes(&data).unwrap().dominant_color(),
+        Some((255, 255, 255))
+    );
+}
+
+/// A 256 entry Local Color Table is read whole even though the raster only uses two of its colors,
+/// so the frame after it still starts at the right byte.
+#[test]
+fn large_local_table_small_raster() {
+    let local = Frame {
+        local_color_table: Some((0..=255).map(|i| [i, 255 - i, 7]).collect()),
+        ..frame(0, 0, 2, 2, vec![0, 3, 3, 0])
+    };
+    let gif = Gif::decode_bytes(&gif(
+        2,
+        2,
+        Some(palette()),
+        &[local, frame(0, 0, 1, 1, vec![1])],
+        &[],
+    ))
+    .unwrap();
+
+    assert_eq!(
+        gif.descriptor_groups[0]
+            .local_color_map
+            .as_ref()
+            .unwrap()
+            .palette()
+            .len(),
+        256
+    );
+    assert_eq!(gif.descriptor_groups.len(), 2);
+    let frames = gif.rgba_frames().unwrap();
+    assert_eq!(
+        frames[0],
+        [0, 255, 7, 255, 3, 252, 7, 255, 3, 252, 7, 255, 0, 255, 7, 255]
+    );
+    assert_eq!(frames[1][..4], [255, 255, 255, 255]);
+}
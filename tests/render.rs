@@ -0,0 +1,297 @@
+mod common;
+
+use gif_parser::error::GifError;
+use gif_parser::gif::Gif;
+use gif_parser::render::{Canvas, MAX_PIXEL_BUFFER_BYTES};
+
+/// A tiny frame on the largest possible Logical Screen: every API that composites onto a canvas
+/// of the screen size reports `ImageTooLarge` instead of aborting the process.
+#[test]
+fn huge_screen_is_image_too_large() {
+    let data = common::gif(
+        u16::MAX,
+        u16::MAX,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![1])],
+        &[],
+    );
+    let gif = Gif::decode_bytes(&data).unwrap();
+    let too_large = |result: Result<_, GifError>| {
+        matches!(
+            result,
+            Err(GifError::ImageTooLarge {
+                width: u16::MAX,
+                height: u16::MAX,
+                offset: Some(6)
+            })
+        )
+    };
+    assert!(too_large(gif.frame_rgba(0).map(drop)));
+    assert!(too_large(gif.rgba_frames().map(drop)));
+    assert!(too_large(gif.canvas_rgba().map(drop)));
+    #[cfg(feature = "apng")]
+    {
+        let dir = std::env::temp_dir().join(format!("gif-parser-huge-{}", std::process::id()));
+        let apng = dir.join("huge.png");
+        assert!(too_large(gif.save_apng(apng.to_str().unwrap())));
+        assert!(!dir.exists());
+    }
+}
+
+/// The pixel buffer cap is checked before allocating: one row past it fails on any host, without touching memory.
+#[test]
+fn pixel_buffer_cap_is_deterministic() {
+    // 65535 x 4096 RGBA pixels fit within the cap, one more row does not
+    assert!(u16::MAX as usize * 4096 * 4 <= MAX_PIXEL_BUFFER_BYTES);
+    assert!(u16::MAX as usize * 4097 * 4 > MAX_PIXEL_BUFFER_BYTES);
+    assert!(matches!(
+        Canvas::try_new(u16::MAX, 4097),
+        Err(GifError::ImageTooLarge {
+            width: u16::MAX,
+            height: 4097,
+            offset: None
+        })
+    ));
+
+    let data = common::gif(
+        u16::MAX,
+        4097,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![1])],
+        &[],
+    );
+    let gif = Gif::decode_bytes(&data).unwrap();
+    assert!(matches!(
+        gif.frame_rgba(0),
+        Err(GifError::ImageTooLarge {
+            offset: Some(6),
+            ..
+        })
+    ));
+}
+
+/// A 2x2 frame at (1, 1) lands on the middle of a 4x4 canvas; at (3, 3) only its top left pixel fits.
+#[test]
+fn render_onto_places_and_clips() {
+    let frames = [
+        common::frame(1, 1, 2, 2, vec![1, 2, 3, 1]),
+        common::frame(3, 3, 2, 2, vec![2, 3, 3, 3]),
+    ];
+    let gif = Gif::decode_bytes(&common::gif(4, 4, Some(common::palette()), &frames, &[])).unwrap();
+    let global_color_map = gif.global_color_map.as_ref();
+    let (black, white, red, green) = ([0; 3], [255; 3], [255, 0, 0], [0, 255, 0]);
+
+    let mut canvas = vec![0u8; 4 * 4 * 3];
+    gif.descriptor_groups[0].render_onto(&mut canvas, 4, 4, global_color_map);
+    let expected: Vec<u8> = [
+        [black, black, black, black],
+        [black, white, red, black],
+        [black, green, white, black],
+        [black, black, black, black],
+    ]
+    .concat()
+    .concat();
+    assert_eq!(canvas, expected);
+
+    gif.descriptor_groups[1].render_onto(&mut canvas, 4, 4, global_color_map);
+    assert_eq!(&canvas[15 * 3..], red);
+    assert_eq!(&canvas[..15 * 3], &expected[..15 * 3]);
+}
+
+/// The first frame comes back at the screen size, three bytes per pixel, matching the opaque pixels
+/// of the first composited frame; an offset frame lands at its position.
+#[test]
+fn first_frame_rgb() {
+    for name in ["a.gif", "clown.gif", "stars.gif"] {
+        let gif = Gif::decode(&common::fixture(name)).unwrap();
+        let (width, height, rgb) = gif.first_frame_rgb().unwrap();
+        assert_eq!(rgb.len(), width as usize * height as usize * 3, "{name}");
+        let rgba = &gif.rgba_frames().unwrap()[0];
+        for (rgba, rgb) in rgba.chunks_exact(4).zip(rgb.chunks_exact(3)) {
+            if rgba[3] == 255 {
+                assert_eq!(&rgba[..3], rgb, "{name}");
+            }
+        }
+    }
+
+    let frames = [common::frame(1, 0, 1, 1, vec![2])];
+    let gif = Gif::decode_bytes(&common::gif(2, 1, Some(common::palette()), &frames, &[])).unwrap();
+    let (width, height, rgb) = gif.first_frame_rgb().unwrap();
+    assert_eq!((width, height), (2, 1));
+    assert_eq!(rgb[3..], [255, 0, 0]);
+}
+
+/// The final canvas shows the second frame over the first: its opaque pixel replaces the first frame's,
+/// its transparent pixel lets the first frame show through.
+#[test]
+fn canvas_rgba_overlays_the_last_frame() {
+    let second = common::Frame {
+        graphic_control: Some((1, 0, Some(3))),
+        ..common::frame(1, 0, 1, 2, vec![2, 3])
+    };
+    let frames = [common::frame(0, 0, 2, 2, vec![1, 1, 1, 1]), second];
+    let gif = Gif::decode_bytes(&common::gif(2, 2, Some(common::palette()), &frames, &[])).unwrap();
+    let (white, red) = ([255, 255, 255, 255], [255, 0, 0, 255]);
+    assert_eq!(
+        gif.canvas_rgba().unwrap(),
+        [white, red, white, white].concat()
+    );
+
+    let gif = Gif::decode(&common::fixture("a.gif")).unwrap();
+    assert_eq!(
+        &gif.canvas_rgba().unwrap(),
+        gif.rgba_frames().unwrap().last().unwrap()
+    );
+}
+
+/// A two color checkerboard maps to the ends of the ramp, with half as many lines as columns per pixel;
+/// a fixture keeps its aspect ratio at the requested width.
+#[test]
+fn ascii_art_lines() {
+    let indices = vec![0, 0, 1, 1, 0, 0, 1, 1, 1, 1, 0, 0, 1, 1, 0, 0];
+    let frames = [common::frame(0, 0, 4, 4, indices)];
+    let gif = Gif::decode_bytes(&common::gif(4, 4, Some(common::palette()), &frames, &[])).unwrap();
+    assert_eq!(
+        gif.to_ascii_art(8),
+        "    @@@@\n    @@@@\n@@@@    \n@@@@    \n"
+    );
+
+    let gif = Gif::decode(&common::fixture("stars.gif")).unwrap();
+    let art = gif.to_ascii_art(80);
+    // 200x178 pixels, each line twice as tall as a column is wide
+    assert_eq!(art.lines().count(), 178 * 80 / 200 / 2);
+    assert!(art.lines().all(|line| line.len() == 80));
+}
+
+/// A Local Color Table only applies to its own frame: the frames around it resolve through the global table.
+#[test]
+fn local_color_table_reverts_to_global() {
+    let local = common::Frame {
+        local_color_table: Some(vec![[1, 2, 3], [4, 5, 6]]),
+        ..common::frame(0, 0, 1, 1, vec![1])
+    };
+    let frames = [
+        common::frame(0, 0, 1, 1, vec![1]),
+        local,
+        common::frame(0, 0, 1, 1, vec![1]),
+    ];
+    let gif = Gif::decode_bytes(&common::gif(1, 1, Some(common::palette()), &frames, &[])).unwrap();
+
+    let rgb: Vec<Vec<u8>> = gif
+        .descriptor_groups
+        .iter()
+        .map(|group| group.render_rgb(gif.global_color_map.as_ref()))
+        .collect();
+    assert_eq!(rgb, [[255, 255, 255], [4, 5, 6], [255, 255, 255]]);
+    assert_eq!(
+        gif.rgba_frames().unwrap(),
+        [[255, 255, 255, 255], [4, 5, 6, 255], [255, 255, 255, 255]]
+    );
+}
+
+/// `pixels` walks a 2x2 frame row by row with frame relative coordinates and resolved colors.
+#[test]
+fn pixels_in_row_major_order() {
+    let frames = [common::frame(1, 1, 2, 2, vec![0, 1, 2, 3])];
+    let gif = Gif::decode_bytes(&common::gif(3, 3, Some(common::palette()), &frames, &[])).unwrap();
+    let pixels: Vec<(u16, u16, (u8, u8, u8))> = gif.descriptor_groups[0]
+        .pixels(gif.global_color_map.as_ref())
+        .collect();
+    assert_eq!(
+        pixels,
+        [
+            (0, 0, (0, 0, 0)),
+            (1, 0, (255, 255, 255)),
+            (0, 1, (255, 0, 0)),
+            (1, 1, (0, 255, 0)),
+        ]
+    );
+}
+
+/// `transparent_index` is the Transparent Color Index only while the Transparency Flag is set.
+#[test]
+fn transparent_index() {
+    let with = |graphic_control| common::Frame {
+        graphic_control,
+        ..common::frame(0, 0, 1, 1, vec![0])
+    };
+    let frames = [
+        with(Some((0, 0, Some(3)))),
+        with(Some((0, 0, None))),
+        with(None),
+    ];
+    let mut data = common::gif(1, 1, Some(common::palette()), &frames, &[]);
+    // give the second Graphic Control Extension an index without setting its flag
+    let second = 13 + 12 + 8 + common::image(&frames[0]).len();
+    assert_eq!(data[second..second + 2], [0x21, 0xF9]);
+    data[second + 6] = 2;
+
+    let gif = Gif::decode_bytes(&data).unwrap();
+    let transparent: Vec<Option<u8>> = gif
+        .descriptor_groups
+        .iter()
+        .map(|group| group.transparent_index())
+        .collect();
+    assert_eq!(transparent, [Some(3), None, None]);
+}
+
+/// Swapping indices 0 and 1 changes the raster and the transparent index, and with a permuted
+/// palette the frame still renders the same.
+#[test]
+fn recolor_swaps_indices() {
+    let frame = common::Frame {
+        graphic_control: Some((0, 0, Some(0))),
+        ..common::frame(0, 0, 2, 2, vec![0, 1, 1, 0])
+    };
+    let data = common::gif(2, 2, Some(common::palette()), &[frame], &[]);
+    let mut mapping: [u8; 256] = std::array::from_fn(|i| i as u8);
+    mapping.swap(0, 1);
+
+    let mut swapped = Gif::decode_bytes(&data).unwrap();
+    swapped.recolor(&mapping, false);
+    let group = &swapped.descriptor_groups[0];
+    assert_eq!(group.raster_data.indices(), [1, 0, 0, 1]);
+    assert_eq!(group.transparent_index(), Some(1));
+
+    let original = Gif::decode_bytes(&data).unwrap();
+    let mut permuted = original.clone();
+    permuted.recolor(&mapping, true);
+    assert_eq!(
+        permuted.descriptor_groups[0].raster_data.indices(),
+        [1, 0, 0, 1]
+    );
+    assert_eq!(
+        permuted.descriptor_groups[0].render_rgb(permuted.global_color_map.as_ref()),
+        original.descriptor_groups[0].render_rgb(original.global_color_map.as_ref())
+    );
+}
+
+/// Frame 1 as displayed is the second frame drawn over what the first left on the canvas.
+#[test]
+fn frame_rgba_composites_earlier_frames() {
+    let frames = [
+        common::frame(0, 0, 2, 1, vec![2, 2]),
+        common::frame(1, 0, 1, 1, vec![3]),
+    ];
+    let gif = Gif::decode_bytes(&common::gif(2, 1, Some(common::palette()), &frames, &[])).unwrap();
+
+    assert_eq!(gif.frame_rgba(0).unwrap(), [255, 0, 0, 255, 255, 0, 0, 255]);
+    assert_eq!(gif.frame_rgba(1).unwrap(), [255, 0, 0, 255, 0, 255, 0, 255]);
+    assert_eq!(gif.frame_rgba(1).unwrap(), gif.rgba_frames().unwrap()[1]);
+    assert!(matches!(
+        gif.frame_rgba(2),
+        Err(GifError::FrameNotFound {
+            index: 2,
+            frame_count: 2
+        })
+    ));
+}
+
+/// The bounding box is the Image Descriptor's position and size.
+#[test]
+fn bounding_box_of_a_frame() {
+    let frames = [common::frame(3, 5, 2, 1, vec![0, 1])];
+    let gif =
+        Gif::decode_bytes(&common::gif(10, 10, Some(common::palette()), &frames, &[])).unwrap();
+    assert_eq!(gif.descriptor_groups[0].bounding_box(), (3, 5, 2, 1));
+}
@@ -0,0 +1,33 @@
+use gif_parser::gif::Gif;
+use std::path::Path;
+
+/// Decodes every fixture in `res/`, encodes it again and decodes the result,
+/// which must display exactly the same frames.
+#[test]
+fn fixtures_survive_encode_decode() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("res");
+    let decoded = Gif::decode_all_in_dir(fixtures.to_str().unwrap());
+    assert!(!decoded.is_empty(), "no fixtures in {}", fixtures.display());
+
+    for (path, original) in decoded {
+        let fixture = path.display();
+        let original = original.unwrap_or_else(|err| panic!("{fixture}: {err}"));
+        let round_trip = Gif::decode_bytes(&original.encode())
+            .unwrap_or_else(|err| panic!("{fixture}: re-encoded stream fails to decode: {err}"));
+
+        assert_eq!(
+            round_trip.descriptor_groups.len(),
+            original.descriptor_groups.len(),
+            "{fixture}: frame count differs"
+        );
+        for (frame, (expected, actual)) in original
+            .rgba_frames()
+            .unwrap()
+            .iter()
+            .zip(round_trip.rgba_frames().unwrap().iter())
+            .enumerate()
+        {
+            assert!(expected == actual, "{fixture}: frame {frame} differs");
+        }
+    }
+}
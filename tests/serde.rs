@@ -0,0 +1,21 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use gif_parser::gif::Gif;
+
+/// The Signature serializes as the string it holds, not as an array of bytes.
+#[test]
+fn signature_serializes_as_text() {
+    let data = common::gif(
+        1,
+        1,
+        Some(common::palette()),
+        &[common::frame(0, 0, 1, 1, vec![1])],
+        &[],
+    );
+    let gif = Gif::decode_bytes(&data).unwrap();
+    let json = serde_json::to_value(&gif).unwrap();
+    assert_eq!(json["signature"]["magic"], "GIF");
+    assert_eq!(json["signature"]["version"], "GIF89a");
+}